@@ -0,0 +1,83 @@
+//! Compares the runtime round count of `do_n_rounds` with the unrolled `permute::<N>`, and
+//! measures the throughput of `AEAD128` and `Hash256` which are built on the latter.
+//!
+//! Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ascon::aead128::AEAD128;
+use ascon::hash256::Hash256;
+use ascon::round;
+use ascon::types::{Key, Nonce};
+
+/// Returns the best time of `f` over a few runs, to leave out the noise of the machine.
+fn best_of(mut f: impl FnMut()) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn bench_permutation<const N: usize>() {
+    const ITERATIONS: u32 = 1_000_000;
+    let mut state = [0x0123456789abcdef; 5];
+
+    let runtime = best_of(|| {
+        for _ in 0..ITERATIONS {
+            round::do_n_rounds(black_box(&mut state), black_box(N));
+        }
+    });
+    let unrolled = best_of(|| {
+        for _ in 0..ITERATIONS {
+            round::permute::<N>(black_box(&mut state));
+        }
+    });
+
+    println!(
+        "p{:<2}  do_n_rounds: {:6.2} ns    permute::<{}>: {:6.2} ns    speedup: {:.2}x",
+        N,
+        runtime.as_nanos() as f64 / ITERATIONS as f64,
+        N,
+        unrolled.as_nanos() as f64 / ITERATIONS as f64,
+        runtime.as_secs_f64() / unrolled.as_secs_f64(),
+    );
+}
+
+/// Prints the throughput of `f` on `len` bytes, in MiB/s.
+fn bench_throughput(name: &str, len: usize, mut f: impl FnMut()) {
+    const ITERATIONS: u32 = 20;
+
+    let elapsed = best_of(|| {
+        for _ in 0..ITERATIONS {
+            f();
+        }
+    });
+
+    println!(
+        "{:<8} {:8.2} MiB/s",
+        name,
+        (len as f64 * ITERATIONS as f64) / elapsed.as_secs_f64() / (1024.0 * 1024.0)
+    );
+}
+
+fn main() {
+    bench_permutation::<6>();
+    bench_permutation::<8>();
+    bench_permutation::<12>();
+
+    let data = vec![0x42; 1 << 20];
+    let key = Key::from([1; 16]);
+    let nonce = Nonce::from([2; 16]);
+
+    bench_throughput("AEAD128", data.len(), || {
+        black_box(AEAD128::encrypt(&key, &nonce, &[], black_box(&data)));
+    });
+    bench_throughput("Hash256", data.len(), || {
+        black_box(Hash256::hash(black_box(&data)));
+    });
+}
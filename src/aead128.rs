@@ -1,12 +1,16 @@
-use log::{debug, error};
-use std::u64;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
 
+#[cfg(feature = "alloc")]
+use crate::round;
 use crate::{
-    round::{self, state_to_str},
-    utils::pad_u64,
+    duplex::{self, LittleEndian},
+    types::{Key, Nonce, Tag},
+    utils::{ct_eq, debug, Zeroize},
+    Error,
 };
 
-#[derive(Debug)]
 pub struct AEAD128 {
     /// 128 bits symmetric key.
     key: [u64; 2],
@@ -16,8 +20,28 @@ pub struct AEAD128 {
     state: [u64; 5],
 }
 
+/// Minimum length (in bytes) of a truncated tag, i.e. 64 bits, as allowed by SP 800-232.
+pub const MIN_TAG_LEN: usize = 8;
+
 const RATE: usize = 16;
 
+impl fmt::Debug for AEAD128 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Only the nonce is public.
+        f.debug_struct("AEAD128")
+            .field("nonce", &self.nonce)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for AEAD128 {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.nonce.zeroize();
+        self.state.zeroize();
+    }
+}
+
 /// This is the initialization vector of `Ascon-AEAD128`.
 /// With, `v`, `a`, `b`, `t`, `r/8` as:
 /// - `v`: Unique identifier of the algorithm.
@@ -32,314 +56,748 @@ const RATE: usize = 16;
 /// | 1        |   12     |     8    |    128    |    16    |
 const IV: u64 = 0x00001000808c0001;
 
-/// Domain separation constant (which is XORed with `state[4]`).
-const DSEP: u64 = 0x80u64 << 56;
-
 impl AEAD128 {
     /// Instanciate a new Ascon-AEAD128.
-    fn new(key: [u8; 16], nonce: [u8; 16]) -> Self {
-        let key = [
-            u64::from_le_bytes([
-                key[0], key[1], key[2], key[3], key[4], key[5], key[6], key[7],
-            ]),
-            u64::from_le_bytes([
-                key[8], key[9], key[10], key[11], key[12], key[13], key[14], key[15],
-            ]),
-        ];
-        let nonce = [
-            u64::from_le_bytes([
-                nonce[0], nonce[1], nonce[2], nonce[3], nonce[4], nonce[5], nonce[6], nonce[7],
-            ]),
-            u64::from_le_bytes([
-                nonce[8], nonce[9], nonce[10], nonce[11], nonce[12], nonce[13], nonce[14],
-                nonce[15],
-            ]),
-        ];
+    fn new(key: &Key, nonce: &Nonce) -> Self {
+        Self::with_key_words(load_key(key), nonce)
+    }
 
+    /// Instanciate a new Ascon-AEAD128 from a key already loaded by [`load_key`].
+    fn with_key_words(key: [u64; 2], nonce: &Nonce) -> Self {
         Self {
             key,
-            nonce,
+            nonce: duplex::load_words::<LittleEndian, 2>(nonce.as_bytes()),
             state: [0; 5],
         }
     }
 
     /// Initialization function for Ascon-AEAD128.
     fn initialize(&mut self) {
-        self.state = [IV, self.key[0], self.key[1], self.nonce[0], self.nonce[1]];
-        debug!(" init 1st key xor: {}", state_to_str(&self.state));
-
-        round::do_n_rounds(&mut self.state, 12);
-
-        self.xor_key();
-        debug!(" init 2nd key xor: {}", state_to_str(&self.state));
+        self.state = duplex::initialize(IV, &self.key, &self.nonce);
     }
 
+    #[cfg(feature = "alloc")]
     /// This function performs `Ascon-AEAD128` encryption.
     ///
     /// Returns `(ciphertext, tag)` as:
     /// - `ciphertext` (`Vec<u8>`): The encrypted bytes.
-    /// - `tag` (`[u8; 16]`): The associated authentication tag.
-    pub fn encrypt(key: [u8; 16], nonce: [u8; 16], ad: &[u8], plain: &[u8]) -> (Vec<u8>, [u8; 16]) {
-        let mut aead128 = Self::new(key, nonce);
+    /// - `tag` (`Tag`): The associated authentication tag.
+    pub fn encrypt(key: &Key, nonce: &Nonce, ad: &[u8], plain: &[u8]) -> (Vec<u8>, Tag) {
+        Self::new(key, nonce).encrypt_message(ad, plain)
+    }
 
+    #[cfg(feature = "alloc")]
+    /// Runs the whole encryption, from the initialization to the tag.
+    fn encrypt_message(mut self, ad: &[u8], plain: &[u8]) -> (Vec<u8>, Tag) {
         // Initialize
-        aead128.initialize();
+        self.initialize();
 
         // Process Associated Data
-        aead128.process_adata(ad);
+        self.process_adata(ad);
 
         // Process Plaintext
-        let cipher = aead128.process_plain(plain);
-        /*
-        println!(
-            "cipher = {{{}}}",
-            cipher[..std::cmp::min(16, cipher.len())]
-                .iter()
-                .map(|b| format!("0x{:02x}", b))
-                .collect::<Vec<String>>()
-                .join(", ")
-        );
-        */
+        let cipher = self.process_plain(plain);
 
         // Finalization
-        aead128.finalize();
+        self.finalize();
 
         // Return the encrypted bytes along its authentication tag.
-        (cipher, aead128.get_tag())
+        (cipher, Tag::from(self.get_tag()))
     }
 
+    #[cfg(feature = "alloc")]
     /// This function performs `Ascon-AEAD128` decryption.
     ///
-    /// Returns `Some(plaintext)` on success, and `None on failure.
+    /// Returns the plaintext on success, and [`Error::AuthenticationFailed`] if the tag does not
+    /// match.
     pub fn decrypt(
-        key: [u8; 16],
-        nonce: [u8; 16],
+        key: &Key,
+        nonce: &Nonce,
         ad: &[u8],
         cipher: &[u8],
-        tag: [u8; 16],
-    ) -> Option<Vec<u8>> {
-        let mut aead128 = Self::new(key, nonce);
+        tag: &Tag,
+    ) -> Result<Vec<u8>, Error> {
+        Self::new(key, nonce).decrypt_message(ad, cipher, tag.as_bytes())
+    }
 
+    #[cfg(feature = "alloc")]
+    /// Runs the whole decryption, from the initialization to the tag check.
+    ///
+    /// `tag` may be truncated: only its `tag.len()` bytes are compared.
+    fn decrypt_message(mut self, ad: &[u8], cipher: &[u8], tag: &[u8]) -> Result<Vec<u8>, Error> {
         // Initialize
-        aead128.initialize();
+        self.initialize();
 
         // Process Associated Data
-        aead128.process_adata(ad);
+        self.process_adata(ad);
 
         // Process cipher
-        let plain = aead128.process_cipher(cipher);
+        let mut plain = self.process_cipher(cipher);
 
         // Finalization
-        aead128.finalize();
-
-        // Retrieve the tag and compare it with the one we were supposed to have.
-        let ptag = aead128.get_tag();
-        if ptag != tag {
-            error!(
-                "INVALID TAG: 0x{:x}{:x} - PTAG: 0x{:x}{:x}",
-                u64::from_le_bytes(tag[0..8].try_into().unwrap()),
-                u64::from_le_bytes(tag[8..16].try_into().unwrap()),
-                u64::from_le_bytes(ptag[0..8].try_into().unwrap()),
-                u64::from_le_bytes(ptag[8..16].try_into().unwrap()),
-            );
+        self.finalize();
+
+        // Retrieve the tag and compare it, in constant time, with the one we were supposed to have.
+        if !ct_eq(&self.get_tag()[..tag.len()], tag) {
+            // Do not leave any unauthenticated plaintext behind.
+            debug!("authentication failed");
+            plain.zeroize();
+            return Err(Error::AuthenticationFailed);
         }
 
-        (ptag == tag).then_some(plain)
+        Ok(plain)
     }
 
-    /// This performs the `Associated Data` absorption of the `Ascon-AEAD128` algorithm.
-    fn process_adata(&mut self, ad: &[u8]) {
-        // We only need 2 temporary u64 in total.
-        let mut t1 = [0; 8];
-        let mut t2 = [0; 8];
-
-        if !ad.is_empty() {
-            let mut iter = ad.chunks_exact(RATE);
-            // Load the exact chunks in S0 and S1 and do 8 rounds.
-            for c in iter.by_ref() {
-                t1.copy_from_slice(&c[0..8]);
-                t2.copy_from_slice(&c[8..16]);
-
-                self.state[0] ^= u64::from_le_bytes(t1);
-                self.state[1] ^= u64::from_le_bytes(t2);
-
-                // Apply 8 rounds to state
-                round::do_n_rounds(&mut self.state, 8);
-            }
-
-            // Pad the last 2 chunks and do 8 rounds.
-            let mut remainder = iter.remainder();
-            t1 = [0; 8];
-            let mut pt = &mut self.state[0];
+    #[cfg(feature = "alloc")]
+    /// Same as [`AEAD128::encrypt`], but the tag is truncated to its first `T` bytes.
+    ///
+    /// `T` must be between [`MIN_TAG_LEN`] and 16, which is checked at compile time:
+    ///
+    /// ```compile_fail
+    /// use ascon::types::{Key, Nonce};
+    ///
+    /// // A 32 bits tag is too short.
+    /// let (key, nonce) = (Key::from([0; 16]), Nonce::from([0; 16]));
+    /// ascon::aead128::AEAD128::encrypt_truncated::<4>(&key, &nonce, &[], &[]);
+    /// ```
+    pub fn encrypt_truncated<const T: usize>(
+        key: &Key,
+        nonce: &Nonce,
+        ad: &[u8],
+        plain: &[u8],
+    ) -> (Vec<u8>, Tag<T>) {
+        const { assert!(T >= MIN_TAG_LEN && T <= 16, "Invalid tag length") };
 
-            if remainder.len() >= 8 {
-                t1.copy_from_slice(&remainder[0..8]);
-                *pt ^= u64::from_le_bytes(t1);
-                remainder = &remainder[8..];
-                pt = &mut self.state[1];
-            }
+        let (cipher, tag) = Self::encrypt(key, nonce, ad, plain);
+        let truncated: [u8; T] = tag.as_bytes()[..T].try_into().unwrap();
 
-            t1 = [0; 8];
-            t1[..remainder.len()].copy_from_slice(remainder);
-            *pt ^= pad_u64(u64::from_le_bytes(t1), remainder.len());
+        (cipher, Tag::from(truncated))
+    }
 
-            debug!("        pad adata: {}", state_to_str(&self.state));
+    #[cfg(feature = "alloc")]
+    /// Same as [`AEAD128::decrypt`], for a tag truncated to its first `T` bytes.
+    ///
+    /// Only these `T` bytes are compared, in constant time. `T` must be between [`MIN_TAG_LEN`] and
+    /// 16, which is checked at compile time.
+    pub fn decrypt_truncated<const T: usize>(
+        key: &Key,
+        nonce: &Nonce,
+        ad: &[u8],
+        cipher: &[u8],
+        tag: &Tag<T>,
+    ) -> Result<Vec<u8>, Error> {
+        const { assert!(T >= MIN_TAG_LEN && T <= 16, "Invalid tag length") };
 
-            // Apply 8 rounds to state
-            round::do_n_rounds(&mut self.state, 8);
-        }
+        Self::new(key, nonce).decrypt_message(ad, cipher, tag.as_bytes())
+    }
 
-        // Domain separation
-        self.state[4] ^= DSEP;
-        debug!("domain separation: {}", state_to_str(&self.state));
+    /// Encrypts a batch of independent messages under the same `key`, several at a time when the
+    /// CPU allows it.
+    ///
+    /// Each message is given as `(nonce, ad, plaintext)`, and gets the same `(ciphertext, tag)`
+    /// as [`AEAD128::encrypt`] would return for it, in the same order.
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_many(key: &Key, messages: &[(&Nonce, &[u8], &[u8])]) -> Vec<(Vec<u8>, Tag)> {
+        Aead128Cipher::new(key).encrypt_many(messages)
     }
 
-    /// This function processes the `plaintext` during `Ascon-AEAD128` encryption.
+    /// This function performs `Ascon-AEAD128` encryption of `buffer` in place, without allocating.
     ///
-    /// TODO: Do a `Stream`...
-    fn process_plain(&mut self, plain: &[u8]) -> Vec<u8> {
-        let mut t1 = [0; 8];
-        let mut out = Vec::with_capacity(plain.len());
+    /// Returns the authentication tag, which is to be sent along the ciphertext.
+    pub fn encrypt_in_place_detached(
+        key: &Key,
+        nonce: &Nonce,
+        ad: &[u8],
+        buffer: &mut [u8],
+    ) -> Tag {
+        Self::new(key, nonce).encrypt_buffer(ad, buffer)
+    }
 
-        let mut iter = plain.chunks_exact(16);
-        for c in iter.by_ref() {
-            t1.copy_from_slice(&c[0..8]);
-            self.state[0] ^= u64::from_le_bytes(t1);
+    /// Runs the whole encryption of `buffer`, in place.
+    fn encrypt_buffer(mut self, ad: &[u8], buffer: &mut [u8]) -> Tag {
+        self.initialize();
+        self.process_adata(ad);
+        self.process_plain_in_place(buffer);
+        self.finalize();
 
-            t1.copy_from_slice(&c[8..16]);
-            self.state[1] ^= u64::from_le_bytes(t1);
+        Tag::from(self.get_tag())
+    }
 
-            out.extend_from_slice(&self.state[0].to_le_bytes());
-            out.extend_from_slice(&self.state[1].to_le_bytes());
+    /// This function performs `Ascon-AEAD128` decryption of `buffer` in place, without allocating.
+    ///
+    /// On failure, `buffer` is wiped so that no unauthenticated plaintext is left in it.
+    pub fn decrypt_in_place_detached(
+        key: &Key,
+        nonce: &Nonce,
+        ad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), Error> {
+        Self::new(key, nonce).decrypt_buffer(ad, buffer, tag)
+    }
 
-            debug!(" absorb plaintext: {}", state_to_str(&self.state));
-            round::do_n_rounds(&mut self.state, 8);
+    /// Runs the whole decryption of `buffer`, in place, and wipes it on failure.
+    fn decrypt_buffer(mut self, ad: &[u8], buffer: &mut [u8], tag: &Tag) -> Result<(), Error> {
+        self.initialize();
+        self.process_adata(ad);
+        self.process_cipher_in_place(buffer);
+        self.finalize();
+
+        if !ct_eq(&self.get_tag(), tag.as_bytes()) {
+            debug!("authentication failed");
+            buffer.zeroize();
+            return Err(Error::AuthenticationFailed);
         }
 
-        // Pad the last 2 chunks and do 8 rounds.
-        let mut remainder = iter.remainder();
-        let mut pt = &mut self.state[0];
+        Ok(())
+    }
 
-        if remainder.len() >= 8 {
-            t1 = [0; 8];
-            t1.copy_from_slice(&remainder[0..8]);
-            *pt ^= u64::from_le_bytes(t1);
+    /// Same as [`AEAD128::encrypt_in_place_detached`], but writes the ciphertext to `out`.
+    ///
+    /// Fails with [`Error::InvalidLength`] if `out` and `plain` have different lengths.
+    pub fn encrypt_into(
+        key: &Key,
+        nonce: &Nonce,
+        ad: &[u8],
+        plain: &[u8],
+        out: &mut [u8],
+    ) -> Result<Tag, Error> {
+        check_len(out, plain)?;
+        out.copy_from_slice(plain);
 
-            out.extend_from_slice(&self.state[0].to_le_bytes());
+        Ok(Self::encrypt_in_place_detached(key, nonce, ad, out))
+    }
 
-            remainder = &remainder[8..];
-            pt = &mut self.state[1];
-        }
+    /// Same as [`AEAD128::decrypt_in_place_detached`], but writes the plaintext to `out`.
+    ///
+    /// Fails with [`Error::InvalidLength`] if `out` and `cipher` have different lengths.
+    pub fn decrypt_into(
+        key: &Key,
+        nonce: &Nonce,
+        ad: &[u8],
+        cipher: &[u8],
+        out: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), Error> {
+        check_len(out, cipher)?;
+        out.copy_from_slice(cipher);
+        Self::decrypt_in_place_detached(key, nonce, ad, out, tag)
+    }
 
-        t1 = [0; 8];
-        t1[..remainder.len()].copy_from_slice(remainder);
-        *pt ^= pad_u64(u64::from_le_bytes(t1), remainder.len());
+    /// This performs the `Associated Data` absorption of the `Ascon-AEAD128` algorithm.
+    fn process_adata(&mut self, ad: &[u8]) {
+        debug!("absorbing {} bytes of associated data", ad.len());
+        duplex::absorb_adata::<LittleEndian, RATE, 8>(&mut self.state, ad);
+    }
 
-        out.extend_from_slice(&(*pt).to_le_bytes()[..remainder.len()]);
+    #[cfg(feature = "alloc")]
+    /// Absorbs one full `RATE` bytes block of `Associated Data`.
+    fn absorb_adata_block(&mut self, block: &[u8]) {
+        duplex::absorb_block::<LittleEndian>(&mut self.state, block);
+        round::permute_encoded::<8>(&mut self.state);
+    }
 
-        debug!("    pad plaintext: {}", state_to_str(&self.state));
+    #[cfg(feature = "alloc")]
+    /// Pads and absorbs the last (shorter than `RATE`) block of `Associated Data`.
+    fn absorb_adata_last(&mut self, remainder: &[u8]) {
+        duplex::absorb_last::<LittleEndian, RATE>(&mut self.state, remainder);
+        round::permute_encoded::<8>(&mut self.state);
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Marks the end of the `Associated Data`.
+    fn separate_domain(&mut self) {
+        duplex::separate_domain::<LittleEndian>(&mut self.state);
+    }
+
+    #[cfg(feature = "alloc")]
+    /// This function processes the `plaintext` during `Ascon-AEAD128` encryption.
+    fn process_plain(&mut self, plain: &[u8]) -> Vec<u8> {
+        let mut out = plain.to_vec();
+        self.process_plain_in_place(&mut out);
 
         out
     }
 
+    /// This function encrypts the whole `buffer` in place.
+    fn process_plain_in_place(&mut self, buffer: &mut [u8]) {
+        debug!("encrypting {} bytes", buffer.len());
+        duplex::encrypt::<LittleEndian, RATE, 8>(&mut self.state, buffer);
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Encrypts in place one full `RATE` bytes block of plaintext.
+    fn encrypt_block(&mut self, block: &mut [u8]) {
+        duplex::encrypt_block::<LittleEndian>(&mut self.state, block);
+        round::permute_encoded::<8>(&mut self.state);
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Pads and encrypts in place the last (shorter than `RATE`) block of plaintext.
+    fn encrypt_last(&mut self, remainder: &mut [u8]) {
+        duplex::encrypt_last::<LittleEndian, RATE>(&mut self.state, remainder);
+    }
+
+    #[cfg(feature = "alloc")]
     /// This function processes the `ciphertext` during `Ascon-AEAD128` decryption.
     fn process_cipher(&mut self, cipher: &[u8]) -> Vec<u8> {
-        let mut tmp_bytes = [0; 8];
-        let mut t1;
+        let mut out = cipher.to_vec();
+        self.process_cipher_in_place(&mut out);
+
+        out
+    }
 
-        let mut out = Vec::with_capacity(cipher.len());
+    /// This function decrypts the whole `buffer` in place.
+    fn process_cipher_in_place(&mut self, buffer: &mut [u8]) {
+        debug!("decrypting {} bytes", buffer.len());
+        duplex::decrypt::<LittleEndian, RATE, 8>(&mut self.state, buffer);
+    }
 
-        let mut iter = cipher.chunks_exact(16);
-        for c in iter.by_ref() {
-            tmp_bytes.copy_from_slice(&c[0..8]);
-            t1 = u64::from_le_bytes(tmp_bytes);
-            out.extend_from_slice(&(self.state[0] ^ t1).to_le_bytes());
-            self.state[0] = t1;
+    #[cfg(feature = "alloc")]
+    /// Decrypts in place one full `RATE` bytes block of ciphertext.
+    fn decrypt_block(&mut self, block: &mut [u8]) {
+        duplex::decrypt_block::<LittleEndian>(&mut self.state, block);
+        round::permute_encoded::<8>(&mut self.state);
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Decrypts in place the last (shorter than `RATE`) block of ciphertext, and pads the state.
+    fn decrypt_last(&mut self, remainder: &mut [u8]) {
+        duplex::decrypt_last::<LittleEndian, RATE>(&mut self.state, remainder);
+    }
+
+    /// This is the finalization step of the `Ascon-AEAD128` algorithm.
+    fn finalize(&mut self) {
+        duplex::finalize::<RATE>(&mut self.state, &self.key, &self.key);
+    }
+
+    /// The tag is the concatenation of S3 and S4.
+    pub fn get_tag(&mut self) -> [u8; 16] {
+        duplex::tag::<LittleEndian>(&self.state)
+    }
+}
+
+/// Loads the 128 bits `key` as two little-endian words.
+fn load_key(key: &Key) -> [u64; 2] {
+    duplex::load_words::<LittleEndian, 2>(key.as_bytes())
+}
+
+/// `Ascon-AEAD128` bound to a key, to encrypt and decrypt many messages.
+///
+/// The key is loaded once, when building the cipher. It is `Send + Sync`, so a single instance can
+/// be shared by every thread (e.g. all the connections of a server), each one using its own nonces.
+#[derive(Clone)]
+pub struct Aead128Cipher {
+    /// 128 bits symmetric key, already loaded as words.
+    key: [u64; 2],
+}
+
+impl fmt::Debug for Aead128Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Aead128Cipher").finish_non_exhaustive()
+    }
+}
+
+impl Drop for Aead128Cipher {
+    fn drop(&mut self) {
+        self.key.zeroize();
+    }
+}
+
+impl Aead128Cipher {
+    pub fn new(key: &Key) -> Self {
+        Self { key: load_key(key) }
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Same as [`AEAD128::encrypt`], with the key of this cipher.
+    pub fn encrypt(&self, nonce: &Nonce, ad: &[u8], plain: &[u8]) -> (Vec<u8>, Tag) {
+        AEAD128::with_key_words(self.key, nonce).encrypt_message(ad, plain)
+    }
+
+    #[cfg(feature = "alloc")]
+    /// Same as [`AEAD128::decrypt`], with the key of this cipher.
+    pub fn decrypt(
+        &self,
+        nonce: &Nonce,
+        ad: &[u8],
+        cipher: &[u8],
+        tag: &Tag,
+    ) -> Result<Vec<u8>, Error> {
+        AEAD128::with_key_words(self.key, nonce).decrypt_message(ad, cipher, tag.as_bytes())
+    }
+
+    /// Same as [`AEAD128::encrypt_in_place_detached`], with the key of this cipher.
+    pub fn encrypt_in_place_detached(&self, nonce: &Nonce, ad: &[u8], buffer: &mut [u8]) -> Tag {
+        AEAD128::with_key_words(self.key, nonce).encrypt_buffer(ad, buffer)
+    }
+
+    /// Same as [`AEAD128::decrypt_in_place_detached`], with the key of this cipher.
+    pub fn decrypt_in_place_detached(
+        &self,
+        nonce: &Nonce,
+        ad: &[u8],
+        buffer: &mut [u8],
+        tag: &Tag,
+    ) -> Result<(), Error> {
+        AEAD128::with_key_words(self.key, nonce).decrypt_buffer(ad, buffer, tag)
+    }
+
+    /// Same as [`AEAD128::encrypt_many`], with the key of this cipher.
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_many(&self, messages: &[(&Nonce, &[u8], &[u8])]) -> Vec<(Vec<u8>, Tag)> {
+        let mut jobs: Vec<_> = messages
+            .iter()
+            .map(|&(nonce, ad, plain)| EncryptJob {
+                aead128: AEAD128::with_key_words(self.key, nonce),
+                ad,
+                buffer: plain.to_vec(),
+                phase: Phase::Start,
+                tag: [0; 16],
+            })
+            .collect();
+        round::lanes::run(&mut jobs);
+
+        jobs.into_iter()
+            .map(|job| (job.buffer, Tag::from(job.tag)))
+            .collect()
+    }
+}
+
+/// Where an [`EncryptJob`] is in the encryption.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy)]
+enum Phase {
+    Start,
+    Initialized,
+    /// Absorbing the `Associated Data` from this offset.
+    Adata(usize),
+    AdataDone,
+    /// Encrypting the buffer from this offset.
+    Plain(usize),
+    Finalized,
+    Done,
+}
+
+/// One message of [`Aead128Cipher::encrypt_many`], encrypted in `buffer`.
+#[cfg(feature = "alloc")]
+struct EncryptJob<'a> {
+    /// Only holds the key and the nonce, the state lives in a lane.
+    aead128: AEAD128,
+    ad: &'a [u8],
+    buffer: Vec<u8>,
+    phase: Phase,
+    tag: [u8; 16],
+}
+
+#[cfg(feature = "alloc")]
+impl round::lanes::LaneJob for EncryptJob<'_> {
+    fn start(&self) -> [u64; 5] {
+        duplex::start(IV, &self.aead128.key, &self.aead128.nonce)
+    }
+
+    /// Goes through the same steps as [`AEAD128::encrypt`], stopping before each permutation.
+    fn step(&mut self, state: &mut [u64; 5]) -> Option<usize> {
+        let key = self.aead128.key;
+
+        loop {
+            match self.phase {
+                Phase::Start => {
+                    self.phase = Phase::Initialized;
+                    return Some(12);
+                }
+                Phase::Initialized => {
+                    duplex::xor_key_at_end(state, &key);
+                    self.phase = if self.ad.is_empty() {
+                        Phase::AdataDone
+                    } else {
+                        Phase::Adata(0)
+                    };
+                }
+                Phase::Adata(offset) => {
+                    let block = &self.ad[offset..];
+                    if block.len() >= RATE {
+                        duplex::absorb_block::<LittleEndian>(state, &block[..RATE]);
+                        self.phase = Phase::Adata(offset + RATE);
+                    } else {
+                        duplex::absorb_last::<LittleEndian, RATE>(state, block);
+                        self.phase = Phase::AdataDone;
+                    }
+                    return Some(8);
+                }
+                Phase::AdataDone => {
+                    duplex::separate_domain::<LittleEndian>(state);
+                    self.phase = Phase::Plain(0);
+                }
+                Phase::Plain(offset) => {
+                    let block = &mut self.buffer[offset..];
+                    if block.len() >= RATE {
+                        duplex::encrypt_block::<LittleEndian>(state, &mut block[..RATE]);
+                        self.phase = Phase::Plain(offset + RATE);
+                        return Some(8);
+                    }
+
+                    duplex::encrypt_last::<LittleEndian, RATE>(state, block);
+                    duplex::xor_key_after_rate::<RATE>(state, &key);
+                    self.phase = Phase::Finalized;
+                    return Some(12);
+                }
+                Phase::Finalized => {
+                    duplex::xor_key_at_end(state, &key);
+                    self.tag = duplex::tag::<LittleEndian>(state);
+                    self.phase = Phase::Done;
+                }
+                Phase::Done => return None,
+            }
+        }
+    }
+}
 
-            tmp_bytes.copy_from_slice(&c[8..16]);
-            t1 = u64::from_le_bytes(tmp_bytes);
-            out.extend_from_slice(&(self.state[1] ^ t1).to_le_bytes());
-            self.state[1] = t1;
+/// Checks that the `out` buffer has the same length as `input`.
+fn check_len(out: &[u8], input: &[u8]) -> Result<(), Error> {
+    if out.len() != input.len() {
+        return Err(Error::InvalidLength {
+            expected: input.len(),
+            actual: out.len(),
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+/// This is the state shared by [`Aead128Encryptor`] and [`Aead128Decryptor`].
+struct Incremental {
+    aead128: AEAD128,
+    /// Input bytes which do not fill a whole `RATE` bytes block yet.
+    buffer: [u8; RATE],
+    /// Number of bytes currently held in `buffer`.
+    buffered: usize,
+    /// Whether some non-empty `Associated Data` was given (empty `Associated Data` is not padded).
+    has_adata: bool,
+    /// Whether the `Associated Data` phase is over.
+    adata_done: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for Incremental {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `buffer` holds some plaintext.
+        f.debug_struct("Incremental")
+            .field("aead128", &self.aead128)
+            .field("adata_done", &self.adata_done)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for Incremental {
+    fn drop(&mut self) {
+        self.buffer.zeroize();
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Incremental {
+    fn new(key: &Key, nonce: &Nonce) -> Self {
+        let mut aead128 = AEAD128::new(key, nonce);
+        aead128.initialize();
 
-            round::do_n_rounds(&mut self.state, 8)
+        Self {
+            aead128,
+            buffer: [0; RATE],
+            buffered: 0,
+            has_adata: false,
+            adata_done: false,
         }
+    }
+
+    /// Moves bytes from `data` to the pending block, and returns whether it is now full.
+    fn fill_buffer(&mut self, data: &mut &[u8]) -> bool {
+        let n = data.len().min(RATE - self.buffered);
+        self.buffer[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+        self.buffered += n;
+        *data = &data[n..];
 
-        // Pad the last 2 chunks and do 8 rounds.
-        let mut remainder = iter.remainder();
-        let mut pt = &mut self.state[0];
+        self.buffered == RATE
+    }
 
-        if remainder.len() >= 8 {
-            tmp_bytes = [0; 8];
-            tmp_bytes.copy_from_slice(&remainder[0..8]);
+    fn update_ad(&mut self, mut ad: &[u8]) -> Result<(), Error> {
+        if self.adata_done {
+            return Err(Error::AssociatedDataAfterMessage);
+        }
+        self.has_adata |= !ad.is_empty();
 
-            out.extend(
-                pt.to_le_bytes()
-                    .iter()
-                    .zip(&remainder[0..8])
-                    .map(|(s, t)| s ^ t),
-            );
-            *pt = u64::from_le_bytes(tmp_bytes);
+        if self.buffered > 0 {
+            if !self.fill_buffer(&mut ad) {
+                return Ok(());
+            }
+            self.aead128.absorb_adata_block(&self.buffer);
+            self.buffered = 0;
+        }
 
-            remainder = &remainder[8..];
-            pt = &mut self.state[1];
+        let mut iter = ad.chunks_exact(RATE);
+        for c in iter.by_ref() {
+            self.aead128.absorb_adata_block(c);
         }
 
-        *pt = pad_u64(*pt, remainder.len());
+        let remainder = iter.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+
+        Ok(())
+    }
 
-        // Store the rest of the message
-        if !remainder.is_empty() {
-            tmp_bytes = [0; 8];
-            tmp_bytes[..remainder.len()].copy_from_slice(remainder);
-            *pt ^= u64::from_le_bytes(tmp_bytes);
-            out.extend_from_slice(&(*pt).to_le_bytes()[..remainder.len()]);
+    /// Ends the `Associated Data` phase, if not already done.
+    fn finish_adata(&mut self) {
+        if self.adata_done {
+            return;
+        }
 
-            // Clear the trailing bytes before setting just the end
-            *pt = (*pt) & (!0u64 << (8 * remainder.len())) ^ u64::from_le_bytes(tmp_bytes);
+        if self.has_adata {
+            self.aead128
+                .absorb_adata_last(&self.buffer[..self.buffered]);
         }
+        self.aead128.separate_domain();
 
-        debug!("   pad ciphertext: {}", state_to_str(&self.state));
+        self.buffered = 0;
+        self.adata_done = true;
+    }
+
+    /// Processes the full blocks available in `input` with `process_block`, and returns them.
+    fn update(&mut self, mut input: &[u8], process_block: fn(&mut AEAD128, &mut [u8])) -> Vec<u8> {
+        self.finish_adata();
+
+        let mut out = Vec::with_capacity(input.len() + self.buffered);
+
+        if self.buffered > 0 {
+            if !self.fill_buffer(&mut input) {
+                return out;
+            }
+            out.extend_from_slice(&self.buffer);
+            process_block(&mut self.aead128, &mut out);
+            self.buffered = 0;
+        }
+
+        let full = input.len() - input.len() % RATE;
+        let start = out.len();
+        out.extend_from_slice(&input[..full]);
+        for c in out[start..].chunks_exact_mut(RATE) {
+            process_block(&mut self.aead128, c);
+        }
+
+        let remainder = &input[full..];
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
 
         out
     }
 
-    /// This is the finalization step of the `Ascon-AEAD128` algorithm.
-    fn finalize(&mut self) {
-        // XOR the key with S2 and S3
-        self.state[2] ^= self.key[0];
-        self.state[3] ^= self.key[1];
+    /// Processes the pending partial block with `process_last`, and returns it along with the tag.
+    fn finalize(mut self, process_last: fn(&mut AEAD128, &mut [u8])) -> (Vec<u8>, [u8; 16]) {
+        self.finish_adata();
 
-        debug!("final 1st key xor: {}", state_to_str(&self.state));
+        let mut out = self.buffer[..self.buffered].to_vec();
+        process_last(&mut self.aead128, &mut out);
 
-        // Do the final 12 rounds
-        round::do_n_rounds(&mut self.state, 12);
+        self.aead128.finalize();
 
-        // Finally, XOR the key with S3 and S4 to get the Tag.
-        self.xor_key();
-        debug!("final 2nd key xor: {}", state_to_str(&self.state));
+        (out, self.aead128.get_tag())
     }
+}
 
-    /// The tag is the concatenation of S3 and S4.
-    pub fn get_tag(&mut self) -> [u8; 16] {
-        let mut tag = [0; 16];
-        tag[0..8].copy_from_slice(&self.state[3].to_le_bytes());
-        tag[8..16].copy_from_slice(&self.state[4].to_le_bytes());
-
-        /*
-        println!(
-            "tag = [{}]",
-            tag.iter()
-                .map(|b| format!("0x{:02x}", b))
-                .collect::<Vec<String>>()
-                .join(", ")
-        );
-        */
-
-        tag
-    }
-
-    /// This small function just XORs the key with the last two `state` words.
-    fn xor_key(&mut self) {
-        self.state[3] ^= self.key[0];
-        self.state[4] ^= self.key[1];
+#[cfg(feature = "alloc")]
+/// Incremental `Ascon-AEAD128` encryption.
+///
+/// Give all the `Associated Data` with [`Aead128Encryptor::update_ad`] first, then the plaintext
+/// with [`Aead128Encryptor::update`], in as many calls as needed. The concatenation of the returned
+/// ciphertext is the same as what [`AEAD128::encrypt`] returns for the whole message.
+#[derive(Debug)]
+pub struct Aead128Encryptor {
+    inner: Incremental,
+}
+
+#[cfg(feature = "alloc")]
+impl Aead128Encryptor {
+    pub fn new(key: &Key, nonce: &Nonce) -> Self {
+        Self {
+            inner: Incremental::new(key, nonce),
+        }
+    }
+
+    /// Absorbs some more `Associated Data`.
+    ///
+    /// Fails with [`Error::AssociatedDataAfterMessage`] if called after
+    /// [`Aead128Encryptor::update`].
+    pub fn update_ad(&mut self, ad: &[u8]) -> Result<(), Error> {
+        self.inner.update_ad(ad)
+    }
+
+    /// Encrypts some more plaintext.
+    ///
+    /// Only whole 16 bytes blocks are encrypted right away, so the returned ciphertext may be
+    /// shorter than `plain`: the trailing bytes are kept until the next call.
+    pub fn update(&mut self, plain: &[u8]) -> Vec<u8> {
+        self.inner.update(plain, AEAD128::encrypt_block)
+    }
+
+    /// Encrypts the remaining buffered plaintext.
+    ///
+    /// Returns `(ciphertext, tag)`, where `ciphertext` is only the last bytes of the message.
+    pub fn finalize(self) -> (Vec<u8>, Tag) {
+        let (cipher, tag) = self.inner.finalize(AEAD128::encrypt_last);
+
+        (cipher, Tag::from(tag))
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Incremental `Ascon-AEAD128` decryption.
+///
+/// This mirrors [`Aead128Encryptor`]. Note that [`Aead128Decryptor::update`] returns plaintext
+/// **before** the tag is checked: it must not be used until [`Aead128Decryptor::finalize`]
+/// succeeded.
+#[derive(Debug)]
+pub struct Aead128Decryptor {
+    inner: Incremental,
+}
+
+#[cfg(feature = "alloc")]
+impl Aead128Decryptor {
+    pub fn new(key: &Key, nonce: &Nonce) -> Self {
+        Self {
+            inner: Incremental::new(key, nonce),
+        }
+    }
+
+    /// Absorbs some more `Associated Data`.
+    ///
+    /// Fails with [`Error::AssociatedDataAfterMessage`] if called after
+    /// [`Aead128Decryptor::update`].
+    pub fn update_ad(&mut self, ad: &[u8]) -> Result<(), Error> {
+        self.inner.update_ad(ad)
+    }
+
+    /// Decrypts some more ciphertext.
+    ///
+    /// Only whole 16 bytes blocks are decrypted right away, so the returned plaintext may be
+    /// shorter than `cipher`: the trailing bytes are kept until the next call.
+    pub fn update(&mut self, cipher: &[u8]) -> Vec<u8> {
+        self.inner.update(cipher, AEAD128::decrypt_block)
+    }
+
+    /// Decrypts the remaining buffered ciphertext and checks `tag`.
+    ///
+    /// Returns the last bytes of the message on success, and [`Error::AuthenticationFailed`] if the
+    /// tag does not match.
+    pub fn finalize(self, tag: &Tag) -> Result<Vec<u8>, Error> {
+        let (mut plain, ptag) = self.inner.finalize(AEAD128::decrypt_last);
+
+        if !ct_eq(&ptag, tag.as_bytes()) {
+            plain.zeroize();
+            return Err(Error::AuthenticationFailed);
+        }
+
+        Ok(plain)
     }
 }
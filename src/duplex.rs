@@ -0,0 +1,266 @@
+//! The duplex steps of the AEAD modes, shared by `Ascon-AEAD128` and the Ascon v1.2 ciphers.
+//!
+//! Both modes initialize, absorb, encrypt, decrypt and finalize the exact same way: they only
+//! differ by the [`Layout`] of the bytes in the state words, and by the padding which follows from
+//! it. The rate `RATE` (in bytes) and the number of rounds `B` per block are const parameters.
+//!
+//! The state is kept in the form given by [`round::encode`]: every word goes through [`load`] and
+//! [`store`].
+
+use crate::{round, utils::Zeroize};
+
+/// How bytes are loaded into the state words, and how the inputs are padded.
+pub(crate) trait Layout {
+    /// Byte appended to the last block: a single `1` bit, in the bit order of the layout.
+    const PAD: u8;
+    /// Last bit of the state, flipped once the `Associated Data` has been absorbed.
+    const DSEP: u64;
+
+    fn load(bytes: [u8; 8]) -> u64;
+
+    fn store(word: u64) -> [u8; 8];
+}
+
+/// `SP 800-232`: little-endian words.
+#[derive(Debug)]
+pub(crate) struct LittleEndian;
+
+impl Layout for LittleEndian {
+    const PAD: u8 = 0x01;
+    const DSEP: u64 = 0x80 << 56;
+
+    #[inline(always)]
+    fn load(bytes: [u8; 8]) -> u64 {
+        u64::from_le_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn store(word: u64) -> [u8; 8] {
+        word.to_le_bytes()
+    }
+}
+
+/// Ascon v1.2: big-endian words.
+#[cfg(feature = "alloc")]
+#[derive(Debug)]
+pub(crate) struct BigEndian;
+
+#[cfg(feature = "alloc")]
+impl Layout for BigEndian {
+    const PAD: u8 = 0x80;
+    const DSEP: u64 = 1;
+
+    #[inline(always)]
+    fn load(bytes: [u8; 8]) -> u64 {
+        u64::from_be_bytes(bytes)
+    }
+
+    #[inline(always)]
+    fn store(word: u64) -> [u8; 8] {
+        word.to_be_bytes()
+    }
+}
+
+/// Loads a word into the state, see [`round::encode`].
+#[inline(always)]
+fn load<L: Layout>(bytes: &[u8]) -> u64 {
+    round::encode(L::load(bytes.try_into().unwrap()))
+}
+
+/// Stores a word of the state, undoing [`load`].
+#[inline(always)]
+fn store<L: Layout>(word: u64) -> [u8; 8] {
+    L::store(round::decode(word))
+}
+
+/// Loads `N` words from `8 * N` bytes.
+pub(crate) fn load_words<L: Layout, const N: usize>(bytes: &[u8]) -> [u64; N] {
+    let mut words = [0; N];
+    for (w, c) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+        *w = load::<L>(c);
+    }
+
+    words
+}
+
+/// The state before the initial permutation: `IV || K || N`, the key words ending right before
+/// the 128 bits nonce.
+pub(crate) fn start(iv: u64, key: &[u64], nonce: &[u64; 2]) -> [u64; 5] {
+    let mut state = [round::encode(iv), 0, 0, nonce[0], nonce[1]];
+    for (s, k) in state[3 - key.len()..3].iter_mut().zip(key) {
+        *s ^= k;
+    }
+
+    state
+}
+
+/// The whole initialization: the initial permutation of [`start`], then the key XORed again.
+pub(crate) fn initialize(iv: u64, key: &[u64], nonce: &[u64; 2]) -> [u64; 5] {
+    let mut state = start(iv, key, nonce);
+    round::permute_encoded::<12>(&mut state);
+    xor_key_at_end(&mut state, key);
+
+    state
+}
+
+/// XORs the key words with the end of the state.
+pub(crate) fn xor_key_at_end(state: &mut [u64; 5], key: &[u64]) {
+    for (s, k) in state[5 - key.len()..].iter_mut().zip(key) {
+        *s ^= k;
+    }
+}
+
+/// XORs the key words right after the rate, as the finalization starts.
+pub(crate) fn xor_key_after_rate<const RATE: usize>(state: &mut [u64; 5], key: &[u64]) {
+    for (s, k) in state[RATE / 8..].iter_mut().zip(key) {
+        *s ^= k;
+    }
+}
+
+/// The whole finalization, `key_after_rate` and `key_at_end` being the key words aligned for
+/// [`xor_key_after_rate`] and [`xor_key_at_end`] respectively. The tag is then given by [`tag`].
+pub(crate) fn finalize<const RATE: usize>(
+    state: &mut [u64; 5],
+    key_after_rate: &[u64],
+    key_at_end: &[u64],
+) {
+    xor_key_after_rate::<RATE>(state, key_after_rate);
+    round::permute_encoded::<12>(state);
+    xor_key_at_end(state, key_at_end);
+}
+
+/// The tag: the last two words of the state.
+pub(crate) fn tag<L: Layout>(state: &[u64; 5]) -> [u8; 16] {
+    let mut tag = [0; 16];
+    tag[0..8].copy_from_slice(&store::<L>(state[3]));
+    tag[8..16].copy_from_slice(&store::<L>(state[4]));
+
+    tag
+}
+
+/// Absorbs the whole `Associated Data` (if any), then separates it from the message.
+pub(crate) fn absorb_adata<L: Layout, const RATE: usize, const B: usize>(
+    state: &mut [u64; 5],
+    ad: &[u8],
+) {
+    if !ad.is_empty() {
+        let mut iter = ad.chunks_exact(RATE);
+        for c in iter.by_ref() {
+            absorb_block::<L>(state, c);
+            round::permute_encoded::<B>(state);
+        }
+
+        absorb_last::<L, RATE>(state, iter.remainder());
+        round::permute_encoded::<B>(state);
+    }
+
+    separate_domain::<L>(state);
+}
+
+/// Marks the end of the `Associated Data`.
+pub(crate) fn separate_domain<L: Layout>(state: &mut [u64; 5]) {
+    state[4] ^= round::encode(L::DSEP);
+}
+
+/// XORs one full block into the rate.
+#[inline(always)]
+pub(crate) fn absorb_block<L: Layout>(state: &mut [u64; 5], block: &[u8]) {
+    for (s, c) in state.iter_mut().zip(block.chunks_exact(8)) {
+        *s ^= load::<L>(c);
+    }
+}
+
+/// Pads the last (shorter than `RATE`) block, which may be empty, and XORs it into the rate.
+pub(crate) fn absorb_last<L: Layout, const RATE: usize>(state: &mut [u64; 5], remainder: &[u8]) {
+    let mut padded = [0; RATE];
+    padded[..remainder.len()].copy_from_slice(remainder);
+    padded[remainder.len()] = L::PAD;
+    absorb_block::<L>(state, &padded);
+    padded.zeroize();
+}
+
+/// Encrypts the whole `buffer` in place. The last block is padded but not permuted, as the
+/// finalization follows.
+pub(crate) fn encrypt<L: Layout, const RATE: usize, const B: usize>(
+    state: &mut [u64; 5],
+    buffer: &mut [u8],
+) {
+    let mut iter = buffer.chunks_exact_mut(RATE);
+    for c in iter.by_ref() {
+        encrypt_block::<L>(state, c);
+        round::permute_encoded::<B>(state);
+    }
+    encrypt_last::<L, RATE>(state, iter.into_remainder());
+}
+
+/// Encrypts in place one full block.
+#[inline(always)]
+pub(crate) fn encrypt_block<L: Layout>(state: &mut [u64; 5], block: &mut [u8]) {
+    for (s, c) in state.iter_mut().zip(block.chunks_exact_mut(8)) {
+        *s ^= load::<L>(c);
+        c.copy_from_slice(&store::<L>(*s));
+    }
+}
+
+/// Pads and encrypts in place the last (shorter than `RATE`) block.
+pub(crate) fn encrypt_last<L: Layout, const RATE: usize>(
+    state: &mut [u64; 5],
+    remainder: &mut [u8],
+) {
+    absorb_last::<L, RATE>(state, remainder);
+
+    let mut block = rate_bytes::<L, RATE>(state);
+    let len = remainder.len();
+    remainder.copy_from_slice(&block[..len]);
+    block.zeroize();
+}
+
+/// Decrypts the whole `buffer` in place, the counterpart of [`encrypt`].
+pub(crate) fn decrypt<L: Layout, const RATE: usize, const B: usize>(
+    state: &mut [u64; 5],
+    buffer: &mut [u8],
+) {
+    let mut iter = buffer.chunks_exact_mut(RATE);
+    for c in iter.by_ref() {
+        decrypt_block::<L>(state, c);
+        round::permute_encoded::<B>(state);
+    }
+    decrypt_last::<L, RATE>(state, iter.into_remainder());
+}
+
+/// Decrypts in place one full block, which then replaces the rate.
+#[inline(always)]
+pub(crate) fn decrypt_block<L: Layout>(state: &mut [u64; 5], block: &mut [u8]) {
+    for (s, c) in state.iter_mut().zip(block.chunks_exact_mut(8)) {
+        let cipher = load::<L>(c);
+        c.copy_from_slice(&store::<L>(*s ^ cipher));
+        *s = cipher;
+    }
+}
+
+/// Decrypts in place the last (shorter than `RATE`) block, and pads the state.
+pub(crate) fn decrypt_last<L: Layout, const RATE: usize>(
+    state: &mut [u64; 5],
+    remainder: &mut [u8],
+) {
+    let mut block = rate_bytes::<L, RATE>(state);
+    for (s, c) in block.iter_mut().zip(remainder.iter_mut()) {
+        (*s, *c) = (*c, *s ^ *c);
+    }
+    block[remainder.len()] ^= L::PAD;
+
+    for (s, c) in state.iter_mut().zip(block.chunks_exact(8)) {
+        *s = load::<L>(c);
+    }
+    block.zeroize();
+}
+
+/// Returns the first `RATE` bytes of the state.
+pub(crate) fn rate_bytes<L: Layout, const RATE: usize>(state: &[u64; 5]) -> [u8; RATE] {
+    let mut out = [0; RATE];
+    for (c, s) in out.chunks_exact_mut(8).zip(state) {
+        c.copy_from_slice(&store::<L>(*s));
+    }
+
+    out
+}
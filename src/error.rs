@@ -0,0 +1,46 @@
+use core::fmt;
+
+/// Errors returned by the fallible functions of this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// The ciphertext (or message) does not match its authentication tag.
+    AuthenticationFailed,
+    /// An input does not have the expected length (in bytes).
+    InvalidLength { expected: usize, actual: usize },
+    /// The requested tag length (in bytes) is not supported by the algorithm.
+    InvalidTagLength { len: usize },
+    /// An input is longer than what the algorithm can process, or a stream holds too many segments.
+    DataLimitExceeded,
+    /// A string is not valid hex.
+    InvalidHex,
+    /// A known answer test file is malformed, from the given line (counted from 1).
+    InvalidKat { line: usize },
+    /// The `count` rounds starting with round `start` go past the last of the 12 rounds.
+    InvalidRounds { start: usize, count: usize },
+    /// `Associated Data` was given after the message had started.
+    AssociatedDataAfterMessage,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AuthenticationFailed => f.write_str("authentication tag mismatch"),
+            Self::InvalidLength { expected, actual } => {
+                write!(f, "expected {} bytes, got {}", expected, actual)
+            }
+            Self::InvalidTagLength { len } => write!(f, "unsupported tag length of {} bytes", len),
+            Self::DataLimitExceeded => f.write_str("data limit of the algorithm exceeded"),
+            Self::InvalidHex => f.write_str("invalid hex string"),
+            Self::InvalidKat { line } => write!(f, "invalid KAT record at line {}", line),
+            Self::InvalidRounds { start, count } => {
+                write!(f, "invalid rounds: {} rounds from round {}", count, start)
+            }
+            Self::AssociatedDataAfterMessage => {
+                f.write_str("associated data given after the message")
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
@@ -1,4 +1,12 @@
-use crate::{round, utils::pad_u64};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    round,
+    utils::{pad_u64, Zeroize},
+    Error,
+};
 
 /// This is the initialization vector of `Ascon-Hash256`.
 /// With, `v`, `a`, `b`, `t`, `r/8` as:
@@ -14,16 +22,57 @@ use crate::{round, utils::pad_u64};
 /// | 2        |   12     |    12    |    256    |    8     |
 const IV: u64 = 0x0000080100cc0002;
 
-#[derive(Debug, Default)]
+/// This is the initialization vector of `Ascon-XOF128`.
+///
+/// Same layout as [`IV`], with these values:
+/// |    v     |     a    |     b    |     t     |    r/8   |
+/// | (8 bits) | (4 bits) | (4 bits) | (16 bits) | (8 bits) |
+/// | 3        |   12     |    12    |     0     |    8     |
+const XOF_IV: u64 = 0x0000080000cc0003;
+
+/// This is the initialization vector of `Ascon-CXOF128`.
+///
+/// Same layout as [`IV`], with these values:
+/// |    v     |     a    |     b    |     t     |    r/8   |
+/// | (8 bits) | (4 bits) | (4 bits) | (16 bits) | (8 bits) |
+/// | 4        |   12     |    12    |     0     |    8     |
+const CXOF_IV: u64 = 0x0000080000cc0004;
+
+/// Maximum length (in bytes) of an `Ascon-CXOF128` customization string, i.e. 2048 bits.
+pub const MAX_CUSTOMIZATION_LEN: usize = 256;
+
 pub struct Hash256 {
     /// 320 bits internal state.
     state: [u64; 5],
+    /// Input bytes which do not fill a whole 64 bits block yet.
+    buffer: [u8; 8],
+    /// Number of bytes currently held in `buffer`.
+    buffered: usize,
+}
+
+impl fmt::Debug for Hash256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hash256").finish_non_exhaustive()
+    }
+}
+
+impl Drop for Hash256 {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.buffer.zeroize();
+    }
+}
+
+impl Default for Hash256 {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Hash256 {
     pub fn hash(data: &[u8]) -> [u8; 32] {
         // Initialize the state using the IV.
-        let mut hash256 = Hash256::initialize();
+        let mut hash256 = Hash256::initialize(IV);
 
         // Process the data to hash
         hash256.process_data(data);
@@ -32,41 +81,261 @@ impl Hash256 {
         hash256.squeeze()
     }
 
-    fn initialize() -> Self {
-        let mut out = Hash256::default();
-        out.state[0] = IV;
-        round::do_n_rounds(&mut out.state, 12);
-        out
+    /// Starts a new incremental `Ascon-Hash256` computation.
+    ///
+    /// Feed it with [`Hash256::update`] and get the digest with [`Hash256::finalize`]. The result
+    /// is the same as [`Hash256::hash`] over the concatenation of all the updates.
+    pub fn new() -> Self {
+        Self::initialize(IV)
     }
 
-    fn process_data(&mut self, data: &[u8]) {
+    /// Absorbs `data`, which can be of any length.
+    ///
+    /// Trailing bytes which do not fill a 64 bits block are kept until the next call.
+    pub fn update(&mut self, mut data: &[u8]) {
+        // Complete the pending block first.
+        if self.buffered > 0 {
+            let n = data.len().min(self.buffer.len() - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+
+            if self.buffered < self.buffer.len() {
+                return;
+            }
+
+            self.absorb_block(self.buffer);
+            self.buffered = 0;
+        }
+
         // Chunk the message into 64bits blocks.
         let mut iter = data.chunks_exact(8);
-        // Safety: We chunked by exact 8 so we should be able to construct a `u64` from each chunk.
-        for c in iter
-            .by_ref()
-            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
-        {
-            self.state[0] ^= c;
-            round::do_n_rounds(&mut self.state, 12);
+        // Safety: We chunked by exact 8 so we should be able to construct an array from each chunk.
+        for c in iter.by_ref() {
+            self.absorb_block(c.try_into().unwrap());
         }
 
-        // Process the last partial block if any.
+        // Keep the last partial block for later.
         let remainder = iter.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffered = remainder.len();
+    }
+
+    /// Pads the last block and returns the digest of everything given to [`Hash256::update`].
+    pub fn finalize(mut self) -> [u8; 32] {
+        self.pad();
+        self.squeeze()
+    }
+
+    fn initialize(iv: u64) -> Self {
+        let mut out = Self {
+            state: [round::encode(iv), 0, 0, 0, 0],
+            buffer: [0; 8],
+            buffered: 0,
+        };
+        round::permute_encoded::<12>(&mut out.state);
+        out
+    }
+
+    /// Absorbs `data` and pads it, as a complete message.
+    fn process_data(&mut self, data: &[u8]) {
+        self.update(data);
+        self.pad();
+    }
+
+    fn absorb_block(&mut self, block: [u8; 8]) {
+        self.state[0] ^= round::encode(u64::from_le_bytes(block));
+        round::permute_encoded::<12>(&mut self.state);
+    }
+
+    /// Processes the last partial block, which may be empty.
+    fn pad(&mut self) {
         let mut t1 = [0; 8];
-        t1[..remainder.len()].copy_from_slice(remainder);
-        let last_c = pad_u64(u64::from_le_bytes(t1), remainder.len());
-        self.state[0] ^= last_c;
+        t1[..self.buffered].copy_from_slice(&self.buffer[..self.buffered]);
+        self.state[0] ^= round::encode(pad_u64(u64::from_le_bytes(t1), self.buffered));
+        t1.zeroize();
+        self.buffered = 0;
     }
 
     /// Squeezing phase
-    fn squeeze(&mut self) -> [u8; 32] {
+    fn squeeze(self) -> [u8; 32] {
         let mut out = [0; 32];
-        for i in 0..4 {
-            round::do_n_rounds(&mut self.state, 12);
-            out[(i * 8)..(i * 8 + 8)].copy_from_slice(&self.state[0].to_le_bytes());
-        }
+        XofReader::new(self.state).squeeze(&mut out);
 
         out
     }
 }
+
+/// Hashes every message of `messages`, several at a time when the CPU allows it.
+///
+/// This gives the same digests as [`Hash256::hash`], in the same order, but is much faster on
+/// batches of small messages.
+#[cfg(feature = "alloc")]
+pub fn hash_many(messages: &[&[u8]]) -> Vec<[u8; 32]> {
+    let mut jobs: Vec<_> = messages.iter().map(|data| HashJob::new(data)).collect();
+    round::lanes::run(&mut jobs);
+
+    jobs.iter().map(|job| job.digest).collect()
+}
+
+/// One message of [`hash_many`].
+#[cfg(feature = "alloc")]
+struct HashJob<'a> {
+    data: &'a [u8],
+    /// Number of permutations already done.
+    permutations: usize,
+    digest: [u8; 32],
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> HashJob<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            permutations: 0,
+            digest: [0; 32],
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl round::lanes::LaneJob for HashJob<'_> {
+    fn start(&self) -> [u64; 5] {
+        [round::encode(IV), 0, 0, 0, 0]
+    }
+
+    /// Goes through the same steps as [`Hash256::hash`]: the initialization, one block per
+    /// permutation, the padded last block, then the 4 squeezed words.
+    fn step(&mut self, state: &mut [u64; 5]) -> Option<usize> {
+        let p = self.permutations;
+        self.permutations += 1;
+
+        if p == 0 {
+            return Some(12);
+        }
+
+        let block = p - 1;
+        let full_blocks = self.data.len() / 8;
+        if block < full_blocks {
+            let c = &self.data[8 * block..8 * block + 8];
+            state[0] ^= round::encode(u64::from_le_bytes(c.try_into().unwrap()));
+        } else if block == full_blocks {
+            let remainder = &self.data[8 * block..];
+            let mut t1 = [0; 8];
+            t1[..remainder.len()].copy_from_slice(remainder);
+            state[0] ^= round::encode(pad_u64(u64::from_le_bytes(t1), remainder.len()));
+            t1.zeroize();
+        } else {
+            let word = block - full_blocks - 1;
+            self.digest[8 * word..8 * word + 8]
+                .copy_from_slice(&round::decode(state[0]).to_le_bytes());
+            if 8 * word + 8 == self.digest.len() {
+                return None;
+            }
+        }
+
+        Some(12)
+    }
+}
+
+/// `Ascon-XOF128`: the extendable-output function of SP 800-232.
+///
+/// It absorbs its input exactly like [`Hash256`] and only differs by its IV, but the caller decides
+/// how many output bytes to squeeze.
+#[derive(Debug)]
+pub struct Xof128;
+
+impl Xof128 {
+    /// Absorbs `data` and returns the reader to squeeze the output from.
+    pub fn xof(data: &[u8]) -> XofReader {
+        let mut xof128 = Hash256::initialize(XOF_IV);
+        xof128.process_data(data);
+
+        XofReader::new(xof128.state)
+    }
+}
+
+/// `Ascon-CXOF128`: the customized extendable-output function of SP 800-232.
+///
+/// The customization string `Z` is absorbed before the message, prefixed by its length in bits,
+/// so that different customizations give independent functions.
+#[derive(Debug)]
+pub struct Cxof128;
+
+impl Cxof128 {
+    /// Absorbs the `customization` string then `data`, and returns the reader to squeeze the
+    /// output from.
+    ///
+    /// Fails with [`Error::DataLimitExceeded`] if `customization` is longer than
+    /// [`MAX_CUSTOMIZATION_LEN`] bytes.
+    pub fn xof(customization: &[u8], data: &[u8]) -> Result<XofReader, Error> {
+        if customization.len() > MAX_CUSTOMIZATION_LEN {
+            return Err(Error::DataLimitExceeded);
+        }
+
+        let mut cxof128 = Hash256::initialize(CXOF_IV);
+
+        // The first block is the bit length of the customization string.
+        cxof128.state[0] ^= round::encode(8 * customization.len() as u64);
+        round::permute_encoded::<12>(&mut cxof128.state);
+
+        // Then comes the padded customization string itself.
+        cxof128.process_data(customization);
+        round::permute_encoded::<12>(&mut cxof128.state);
+
+        cxof128.process_data(data);
+
+        Ok(XofReader::new(cxof128.state))
+    }
+}
+
+/// Squeezing side of `Ascon-XOF128` and `Ascon-CXOF128`.
+///
+/// Successive calls to [`XofReader::squeeze`] continue the output stream where the previous call
+/// stopped, so reading `n` then `m` bytes gives the same bytes as reading `n + m` at once.
+pub struct XofReader {
+    /// 320 bits internal state.
+    state: [u64; 5],
+    /// The current 64 bits output block.
+    block: [u8; 8],
+    /// Number of bytes of `block` already returned.
+    pos: usize,
+}
+
+impl fmt::Debug for XofReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XofReader").finish_non_exhaustive()
+    }
+}
+
+impl Drop for XofReader {
+    fn drop(&mut self) {
+        self.state.zeroize();
+        self.block.zeroize();
+    }
+}
+
+impl XofReader {
+    fn new(state: [u64; 5]) -> Self {
+        Self {
+            state,
+            block: [0; 8],
+            // Nothing buffered yet: the first read has to permute the state.
+            pos: 8,
+        }
+    }
+
+    /// Fills `out` with the next `out.len()` bytes of output.
+    pub fn squeeze(&mut self, out: &mut [u8]) {
+        for b in out.iter_mut() {
+            if self.pos == self.block.len() {
+                round::permute_encoded::<12>(&mut self.state);
+                self.block = round::decode(self.state[0]).to_le_bytes();
+                self.pos = 0;
+            }
+
+            *b = self.block[self.pos];
+            self.pos += 1;
+        }
+    }
+}
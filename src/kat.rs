@@ -0,0 +1,200 @@
+//! Known answer tests, in the format of the NIST Lightweight Cryptography project.
+//!
+//! A KAT file is a list of records separated by blank lines, each field of a record being a
+//! `Name = VALUE` line. `Count` is a decimal number and the other values are hex strings, which
+//! are empty for empty inputs:
+//!
+//! ```text
+//! Count = 2
+//! Msg = 00
+//! MD = 0728621035AF3ED2BCA03BF6FDE900F9456F5330E4B5EE23E7F6A1E70291BC80
+//! ```
+//!
+//! [`write_record`] writes records in the exact same layout, with uppercase hex.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    types::{decode_hex_vec, Key, Nonce},
+    Error,
+};
+
+/// A record of an `Ascon-AEAD128` KAT file.
+#[derive(Debug, Clone)]
+pub struct AeadKat {
+    pub count: usize,
+    pub key: Key,
+    pub nonce: Nonce,
+    /// `PT`
+    pub plain: Vec<u8>,
+    /// `AD`
+    pub ad: Vec<u8>,
+    /// `CT`: the ciphertext followed by the tag.
+    pub cipher: Vec<u8>,
+}
+
+/// A record of an `Ascon-Hash256` KAT file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashKat {
+    pub count: usize,
+    /// `Msg`
+    pub msg: Vec<u8>,
+    /// `MD`
+    pub digest: Vec<u8>,
+}
+
+impl fmt::Display for AeadKat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_record(
+            f,
+            self.count,
+            &[
+                ("Key", self.key.as_bytes()),
+                ("Nonce", self.nonce.as_bytes()),
+                ("PT", &self.plain),
+                ("AD", &self.ad),
+                ("CT", &self.cipher),
+            ],
+        )
+    }
+}
+
+impl fmt::Display for HashKat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_record(f, self.count, &[("Msg", &self.msg), ("MD", &self.digest)])
+    }
+}
+
+/// Writes a record: its `Count`, then each of `fields` as uppercase hex, then a blank line.
+///
+/// This works for any algorithm, e.g. with `Key`, `Msg` and `Tag` fields for a MAC.
+pub fn write_record(
+    out: &mut impl fmt::Write,
+    count: usize,
+    fields: &[(&str, &[u8])],
+) -> fmt::Result {
+    writeln!(out, "Count = {}", count)?;
+    for (name, value) in fields {
+        write!(out, "{} = ", name)?;
+        for b in *value {
+            write!(out, "{:02X}", b)?;
+        }
+        writeln!(out)?;
+    }
+
+    writeln!(out)
+}
+
+/// Parses the records of an AEAD KAT file, whose fields are `Count`, `Key`, `Nonce`, `PT`, `AD`
+/// and `CT` in this order.
+///
+/// Returns [`Error::InvalidKat`] with the line of the first malformed field.
+pub fn parse_aead(text: &str) -> Result<Vec<AeadKat>, Error> {
+    records(text)?
+        .iter()
+        .map(|r| {
+            let [count, key, nonce, plain, ad, cipher] =
+                r.fields(["Count", "Key", "Nonce", "PT", "AD", "CT"])?;
+
+            Ok(AeadKat {
+                count: r.count(count)?,
+                key: key.parse().map_err(|_| r.error(1))?,
+                nonce: nonce.parse().map_err(|_| r.error(2))?,
+                plain: r.hex(3, plain)?,
+                ad: r.hex(4, ad)?,
+                cipher: r.hex(5, cipher)?,
+            })
+        })
+        .collect()
+}
+
+/// Parses the records of a hash KAT file, whose fields are `Count`, `Msg` and `MD` in this order.
+///
+/// Returns [`Error::InvalidKat`] with the line of the first malformed field.
+pub fn parse_hash(text: &str) -> Result<Vec<HashKat>, Error> {
+    records(text)?
+        .iter()
+        .map(|r| {
+            let [count, msg, digest] = r.fields(["Count", "Msg", "MD"])?;
+
+            Ok(HashKat {
+                count: r.count(count)?,
+                msg: r.hex(1, msg)?,
+                digest: r.hex(2, digest)?,
+            })
+        })
+        .collect()
+}
+
+/// The raw fields of a record.
+struct Record<'a> {
+    /// Line of the first field, counted from 1.
+    line: usize,
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+/// Splits `text` into records, without looking at the fields.
+fn records(text: &str) -> Result<Vec<Record<'_>>, Error> {
+    let mut records = Vec::new();
+    let mut current: Option<Record> = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            records.extend(current.take());
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once('=')
+            .ok_or(Error::InvalidKat { line: i + 1 })?;
+
+        current
+            .get_or_insert_with(|| Record {
+                line: i + 1,
+                fields: Vec::new(),
+            })
+            .fields
+            .push((name.trim(), value.trim()));
+    }
+    records.extend(current);
+
+    Ok(records)
+}
+
+impl<'a> Record<'a> {
+    /// Returns the values of the fields, which must be exactly `names` in this order.
+    fn fields<const N: usize>(&self, names: [&str; N]) -> Result<[&'a str; N], Error> {
+        let mut values = [""; N];
+        for (i, name) in names.iter().enumerate() {
+            match self.fields.get(i) {
+                Some((n, v)) if n == name => values[i] = v,
+                _ => return Err(self.error(i.min(self.fields.len()))),
+            }
+        }
+
+        if self.fields.len() > N {
+            return Err(self.error(N));
+        }
+
+        Ok(values)
+    }
+
+    /// Error on the `field`-th line of the record.
+    fn error(&self, field: usize) -> Error {
+        Error::InvalidKat {
+            line: self.line + field,
+        }
+    }
+
+    /// Parses the `Count`, always the first field.
+    fn count(&self, value: &str) -> Result<usize, Error> {
+        value.parse().map_err(|_| self.error(0))
+    }
+
+    /// Decodes the hex `value` of the `field`-th field.
+    fn hex(&self, field: usize, value: &str) -> Result<Vec<u8>, Error> {
+        decode_hex_vec(value).map_err(|_| self.error(field))
+    }
+}
@@ -0,0 +1,197 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    duplex::{self, BigEndian},
+    types::{Key, Key160, Nonce, Tag},
+    utils::Zeroize,
+    Error,
+};
+
+/// This is the initialization vector of `Ascon-128` (v1.2).
+/// With, `k`, `r`, `a`, `b` as:
+/// - `k`: Key length in bits.
+/// - `r`: Rate in bits, i.e. number of bits processed per invocation of the permutation.
+/// - `a`: Number of rounds during initialization and finalization.
+/// - `b`: Number of rounds during the processing of `Associated Data`, plaintext and ciphertext.
+///
+/// |    k     |     r    |     a    |     b    |
+/// | (8 bits) | (8 bits) | (8 bits) | (8 bits) |
+/// | 128      |    64    |    12    |     6    |
+const ASCON_128_IV: u64 = 0x80400c0600000000;
+
+/// This is the initialization vector of `Ascon-128a` (v1.2).
+///
+/// |    k     |     r    |     a    |     b    |
+/// | (8 bits) | (8 bits) | (8 bits) | (8 bits) |
+/// | 128      |   128    |    12    |     8    |
+const ASCON_128A_IV: u64 = 0x80800c0800000000;
+
+/// This is the initialization vector of `Ascon-80pq` (v1.2).
+///
+/// |    k     |     r    |     a    |     b    |
+/// | (8 bits) | (8 bits) | (8 bits) | (8 bits) |
+/// | 160      |    64    |    12    |     6    |
+///
+/// Its 32 low bits hold the first 32 bits of the key.
+const ASCON_80PQ_IV: u64 = 0xa0400c0600000000;
+
+/// `Ascon-128`, the primary AEAD of the Ascon v1.2 submission.
+///
+/// Unlike [`crate::aead128::AEAD128`], the v1.2 ciphers load words in big-endian and pad with a
+/// `0x80` byte, so their outputs are unrelated to the ones of `Ascon-AEAD128`.
+#[derive(Debug)]
+pub struct Ascon128;
+
+impl Ascon128 {
+    /// This function performs `Ascon-128` encryption.
+    ///
+    /// Returns `(ciphertext, tag)`.
+    pub fn encrypt(key: &Key, nonce: &Nonce, ad: &[u8], plain: &[u8]) -> (Vec<u8>, Tag) {
+        LegacyAead::<8, 6>::new(ASCON_128_IV, key.as_bytes(), nonce).encrypt(ad, plain)
+    }
+
+    /// This function performs `Ascon-128` decryption.
+    ///
+    /// Returns the plaintext on success, and [`Error::AuthenticationFailed`] on failure.
+    pub fn decrypt(
+        key: &Key,
+        nonce: &Nonce,
+        ad: &[u8],
+        cipher: &[u8],
+        tag: &Tag,
+    ) -> Result<Vec<u8>, Error> {
+        LegacyAead::<8, 6>::new(ASCON_128_IV, key.as_bytes(), nonce).decrypt(ad, cipher, tag)
+    }
+}
+
+/// `Ascon-128a`, the faster AEAD of the Ascon v1.2 submission (128 bits rate, 8 rounds).
+#[derive(Debug)]
+pub struct Ascon128a;
+
+impl Ascon128a {
+    /// This function performs `Ascon-128a` encryption.
+    ///
+    /// Returns `(ciphertext, tag)`.
+    pub fn encrypt(key: &Key, nonce: &Nonce, ad: &[u8], plain: &[u8]) -> (Vec<u8>, Tag) {
+        LegacyAead::<16, 8>::new(ASCON_128A_IV, key.as_bytes(), nonce).encrypt(ad, plain)
+    }
+
+    /// This function performs `Ascon-128a` decryption.
+    ///
+    /// Returns the plaintext on success, and [`Error::AuthenticationFailed`] on failure.
+    pub fn decrypt(
+        key: &Key,
+        nonce: &Nonce,
+        ad: &[u8],
+        cipher: &[u8],
+        tag: &Tag,
+    ) -> Result<Vec<u8>, Error> {
+        LegacyAead::<16, 8>::new(ASCON_128A_IV, key.as_bytes(), nonce).decrypt(ad, cipher, tag)
+    }
+}
+
+/// `Ascon-80pq`, the `Ascon-128` variant with a 160 bits key.
+#[derive(Debug)]
+pub struct Ascon80pq;
+
+impl Ascon80pq {
+    /// This function performs `Ascon-80pq` encryption.
+    ///
+    /// Returns `(ciphertext, tag)`.
+    pub fn encrypt(key: &Key160, nonce: &Nonce, ad: &[u8], plain: &[u8]) -> (Vec<u8>, Tag) {
+        LegacyAead::<8, 6>::new(ASCON_80PQ_IV, key.as_bytes(), nonce).encrypt(ad, plain)
+    }
+
+    /// This function performs `Ascon-80pq` decryption.
+    ///
+    /// Returns the plaintext on success, and [`Error::AuthenticationFailed`] on failure.
+    pub fn decrypt(
+        key: &Key160,
+        nonce: &Nonce,
+        ad: &[u8],
+        cipher: &[u8],
+        tag: &Tag,
+    ) -> Result<Vec<u8>, Error> {
+        LegacyAead::<8, 6>::new(ASCON_80PQ_IV, key.as_bytes(), nonce).decrypt(ad, cipher, tag)
+    }
+}
+
+/// The Ascon v1.2 AEAD mode, for a rate of `RATE` bytes and `B` rounds per block.
+struct LegacyAead<const RATE: usize, const B: usize> {
+    /// The key as a 192 bits big-endian number, i.e. the key words aligned to the end of the state.
+    key: [u64; 3],
+    /// The key words as they are XORed right after the rate during finalization.
+    final_key: [u64; 3],
+    /// 320 bits internal state.
+    state: [u64; 5],
+}
+
+impl<const RATE: usize, const B: usize> fmt::Debug for LegacyAead<RATE, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LegacyAead").finish_non_exhaustive()
+    }
+}
+
+impl<const RATE: usize, const B: usize> Drop for LegacyAead<RATE, B> {
+    fn drop(&mut self) {
+        self.key.zeroize();
+        self.final_key.zeroize();
+        self.state.zeroize();
+    }
+}
+
+impl<const RATE: usize, const B: usize> LegacyAead<RATE, B> {
+    /// Instanciates the cipher with a 128 or 160 bits `key`, and initializes it.
+    fn new(iv: u64, key: &[u8], nonce: &Nonce) -> Self {
+        let mut aligned = [0; 24];
+        aligned[24 - key.len()..].copy_from_slice(key);
+        let mut trailing = [0; 24];
+        trailing[..key.len()].copy_from_slice(key);
+
+        let key = duplex::load_words::<BigEndian, 3>(&aligned);
+        let final_key = duplex::load_words::<BigEndian, 3>(&trailing);
+
+        aligned.zeroize();
+        trailing.zeroize();
+
+        let nonce = duplex::load_words::<BigEndian, 2>(nonce.as_bytes());
+        let state = duplex::initialize(iv, &key, &nonce);
+
+        Self {
+            key,
+            final_key,
+            state,
+        }
+    }
+
+    fn encrypt(mut self, ad: &[u8], plain: &[u8]) -> (Vec<u8>, Tag) {
+        duplex::absorb_adata::<BigEndian, RATE, B>(&mut self.state, ad);
+
+        let mut cipher = plain.to_vec();
+        duplex::encrypt::<BigEndian, RATE, B>(&mut self.state, &mut cipher);
+
+        (cipher, Tag::from(self.finalize()))
+    }
+
+    fn decrypt(mut self, ad: &[u8], cipher: &[u8], tag: &Tag) -> Result<Vec<u8>, Error> {
+        duplex::absorb_adata::<BigEndian, RATE, B>(&mut self.state, ad);
+
+        let mut plain = cipher.to_vec();
+        duplex::decrypt::<BigEndian, RATE, B>(&mut self.state, &mut plain);
+
+        if Tag::from(self.finalize()) != *tag {
+            plain.zeroize();
+            return Err(Error::AuthenticationFailed);
+        }
+
+        Ok(plain)
+    }
+
+    /// This is the finalization step, which returns the tag.
+    fn finalize(&mut self) -> [u8; 16] {
+        duplex::finalize::<RATE>(&mut self.state, &self.final_key, &self.key);
+
+        duplex::tag::<BigEndian>(&self.state)
+    }
+}
@@ -0,0 +1,130 @@
+use core::fmt;
+
+use crate::{round, utils::Zeroize};
+
+/// This is the initialization vector of `Ascon-Hash` (v1.2).
+/// With, `r`, `a`, `a - b`, `t` as:
+/// - `r`: Rate in bits, i.e. number of bits processed per invocation of the permutation.
+/// - `a`: Number of rounds during initialization and finalization.
+/// - `a - b`: Difference with the number of rounds during the processing of the message.
+/// - `t`: Output length in bits, `0` for an arbitrary length.
+///
+/// |          |     r    |     a    |   a - b  |     t     |
+/// | (8 bits) | (8 bits) | (8 bits) | (8 bits) | (32 bits) |
+/// | 0        |    64    |    12    |     0    |    256    |
+const HASH_IV: u64 = 0x00400c0000000100;
+
+/// This is the initialization vector of `Ascon-Hasha` (v1.2): 8 rounds per block (`a - b = 4`).
+const HASHA_IV: u64 = 0x00400c0400000100;
+
+/// This is the initialization vector of `Ascon-Xof` (v1.2): the one of `Ascon-Hash` with `t = 0`.
+const XOF_IV: u64 = 0x00400c0000000000;
+
+/// This is the initialization vector of `Ascon-Xofa` (v1.2): the one of `Ascon-Hasha` with `t = 0`.
+const XOFA_IV: u64 = 0x00400c0400000000;
+
+/// `Ascon-Hash` v1.2, with a 256 bits output.
+///
+/// Unlike [`crate::hash256::Hash256`], the v1.2 functions load words in big-endian and pad with a
+/// `0x80` byte, so their outputs are unrelated to the ones of `Ascon-Hash256`.
+#[derive(Debug)]
+pub struct AsconHash;
+
+impl AsconHash {
+    pub fn hash(data: &[u8]) -> [u8; 32] {
+        let mut out = [0; 32];
+        LegacyHash::<12>::new(HASH_IV).digest(data, &mut out);
+
+        out
+    }
+}
+
+/// `Ascon-Hasha` v1.2, the faster `Ascon-Hash` variant with 8 rounds per message block.
+#[derive(Debug)]
+pub struct AsconHasha;
+
+impl AsconHasha {
+    pub fn hash(data: &[u8]) -> [u8; 32] {
+        let mut out = [0; 32];
+        LegacyHash::<8>::new(HASHA_IV).digest(data, &mut out);
+
+        out
+    }
+}
+
+/// `Ascon-Xof` v1.2, the extendable-output function.
+#[derive(Debug)]
+pub struct AsconXof;
+
+impl AsconXof {
+    /// Fills `out` with the output over `data`.
+    pub fn xof(data: &[u8], out: &mut [u8]) {
+        LegacyHash::<12>::new(XOF_IV).digest(data, out);
+    }
+}
+
+/// `Ascon-Xofa` v1.2, the faster `Ascon-Xof` variant with 8 rounds per block.
+#[derive(Debug)]
+pub struct AsconXofa;
+
+impl AsconXofa {
+    /// Fills `out` with the output over `data`.
+    pub fn xof(data: &[u8], out: &mut [u8]) {
+        LegacyHash::<8>::new(XOFA_IV).digest(data, out);
+    }
+}
+
+/// The Ascon v1.2 hashing mode, with `B` rounds per block.
+struct LegacyHash<const B: usize> {
+    /// 320 bits internal state.
+    state: [u64; 5],
+}
+
+impl<const B: usize> fmt::Debug for LegacyHash<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LegacyHash").finish_non_exhaustive()
+    }
+}
+
+impl<const B: usize> Drop for LegacyHash<B> {
+    fn drop(&mut self) {
+        self.state.zeroize();
+    }
+}
+
+impl<const B: usize> LegacyHash<B> {
+    fn new(iv: u64) -> Self {
+        let mut out = Self {
+            state: [round::encode(iv), 0, 0, 0, 0],
+        };
+        round::permute_encoded::<12>(&mut out.state);
+
+        out
+    }
+
+    /// Absorbs the whole `data` and squeezes `out.len()` bytes.
+    fn digest(mut self, data: &[u8], out: &mut [u8]) {
+        let mut iter = data.chunks_exact(8);
+        for c in iter.by_ref() {
+            self.state[0] ^= round::encode(u64::from_be_bytes(c.try_into().unwrap()));
+            round::permute_encoded::<B>(&mut self.state);
+        }
+
+        // Pad the last block, which may be empty, with a single `1` bit.
+        let remainder = iter.remainder();
+        let mut t1 = [0; 8];
+        t1[..remainder.len()].copy_from_slice(remainder);
+        t1[remainder.len()] = 0x80;
+        self.state[0] ^= round::encode(u64::from_be_bytes(t1));
+        t1.zeroize();
+
+        round::permute_encoded::<12>(&mut self.state);
+
+        for (i, c) in out.chunks_mut(8).enumerate() {
+            if i > 0 {
+                round::permute_encoded::<B>(&mut self.state);
+            }
+            c.copy_from_slice(&round::decode(self.state[0]).to_be_bytes()[..c.len()]);
+        }
+    }
+}
@@ -1,6 +1,27 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod aead128;
+mod duplex;
+mod error;
 pub mod hash256;
+#[cfg(feature = "alloc")]
+pub mod kat;
+#[cfg(feature = "alloc")]
+pub mod legacy_aead;
+pub mod legacy_hash;
+pub mod mac;
+pub mod permutation;
+#[cfg(all(feature = "alloc", any(test, feature = "reference")))]
+pub mod reference;
 pub mod round;
-#[cfg(test)]
+#[cfg(feature = "alloc")]
+pub mod stream;
+#[cfg(all(test, feature = "alloc"))]
 pub mod tests;
+pub mod types;
 pub mod utils;
+
+pub use error::Error;
@@ -0,0 +1,225 @@
+use crate::{
+    round,
+    types::{Key, Tag},
+    utils::Zeroize,
+    Error,
+};
+
+/// This is the initialization vector of `Ascon-Mac`.
+/// With, `k`, `r`, `a`, `b`, `t` as:
+/// - `k`: Key length in bits.
+/// - `r`: Output rate in bits, i.e. number of bits squeezed per invocation of the permutation.
+/// - `a`: Number of rounds, with the most significant bit set (`0x80 | a`).
+/// - `b`: Zero, as every permutation uses `a` rounds.
+/// - `t`: Output length in bits, `0` for an arbitrary length.
+///
+/// For `Ascon-Mac`, these values are:
+/// |    k     |     r    |     a    |     b    |     t     |
+/// | (8 bits) | (8 bits) | (8 bits) | (8 bits) | (32 bits) |
+/// | 128      |   128    |    12    |     0    |    128    |
+const MAC_IV: u64 = 0x80808c0000000080;
+
+/// This is the initialization vector of `Ascon-Prf`: the one of `Ascon-Mac` with `t = 0`.
+const PRF_IV: u64 = 0x80808c0000000000;
+
+/// This is the initialization vector of `Ascon-PrfShort`.
+/// With, `k`, `m`, `a`, `t` as:
+/// - `k`: Key length in bits.
+/// - `m`: Message length in bits (filled in at runtime).
+/// - `a`: Number of rounds, with the second most significant bit set (`0x40 | a`).
+/// - `t`: Output length in bits (filled in at runtime).
+///
+/// |    k     |     m    |     a    |     t    |
+/// | (8 bits) | (8 bits) | (8 bits) | (8 bits) |
+/// | 128      |    -     |    12    |     -    |
+const PRFS_IV: u64 = 0x80004c0000000000;
+
+/// Number of message bytes absorbed per invocation of the permutation, i.e. `x0..x3`.
+///
+/// Only `Ascon-Maca` and `Ascon-Prfa` absorb into `x4` as well, with a 40 bytes rate.
+const MSG_RATE: usize = 32;
+
+/// Number of output bytes squeezed per invocation of the permutation.
+const OUT_RATE: usize = 16;
+
+/// Maximum length (in bytes) of both the message and the output of `Ascon-PrfShort`.
+pub const PRF_SHORT_MAX_LEN: usize = 16;
+
+/// `Ascon-Mac`: message authentication code with a 128 bits tag.
+///
+/// Like the other keyed functions of this module, it follows the Ascon v1.2 reference
+/// implementation, so words are loaded in big-endian.
+#[derive(Debug)]
+pub struct AsconMac;
+
+impl AsconMac {
+    /// Computes the authentication tag of `msg`.
+    pub fn mac(key: &Key, msg: &[u8]) -> Tag {
+        let mut tag = [0; 16];
+        let mut state = initialize(MAC_IV, key);
+        absorb(&mut state, msg);
+        squeeze(&mut state, &mut tag);
+        state.zeroize();
+
+        Tag::from(tag)
+    }
+
+    /// Checks `tag` against `msg`, in constant time.
+    ///
+    /// Fails with [`Error::AuthenticationFailed`] if they do not match.
+    pub fn verify(key: &Key, msg: &[u8], tag: &Tag) -> Result<(), Error> {
+        if Self::mac(key, msg) != *tag {
+            return Err(Error::AuthenticationFailed);
+        }
+
+        Ok(())
+    }
+}
+
+/// `Ascon-Prf`: pseudorandom function with an arbitrary output length.
+#[derive(Debug)]
+pub struct AsconPrf;
+
+impl AsconPrf {
+    /// Fills `out` with the output of the PRF over `msg`.
+    pub fn prf(key: &Key, msg: &[u8], out: &mut [u8]) {
+        let mut state = initialize(PRF_IV, key);
+        absorb(&mut state, msg);
+        squeeze(&mut state, out);
+        state.zeroize();
+    }
+}
+
+/// `Ascon-PrfShort`: pseudorandom function for messages of at most 128 bits, which only needs a
+/// single permutation.
+#[derive(Debug)]
+pub struct AsconPrfShort;
+
+impl AsconPrfShort {
+    /// Fills `out` with the output of the PRF over `msg`.
+    ///
+    /// Fails with [`Error::DataLimitExceeded`] if `msg` is longer than [`PRF_SHORT_MAX_LEN`] bytes,
+    /// and with [`Error::InvalidTagLength`] if `out` is.
+    pub fn prf(key: &Key, msg: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        if msg.len() > PRF_SHORT_MAX_LEN {
+            return Err(Error::DataLimitExceeded);
+        }
+        if out.len() > PRF_SHORT_MAX_LEN {
+            return Err(Error::InvalidTagLength { len: out.len() });
+        }
+
+        let mut padded = [0; 16];
+        padded[..msg.len()].copy_from_slice(msg);
+
+        let mut key = key_words(key);
+        let iv = PRFS_IV | (8 * msg.len() as u64) << 48 | (8 * out.len() as u64) << 32;
+        let mut state = [
+            round::encode(iv),
+            key[0],
+            key[1],
+            load(&padded[0..8]),
+            load(&padded[8..16]),
+        ];
+
+        round::permute_encoded::<12>(&mut state);
+
+        let mut tag = [0; 16];
+        tag[0..8].copy_from_slice(&store(state[3] ^ key[0]));
+        tag[8..16].copy_from_slice(&store(state[4] ^ key[1]));
+        out.copy_from_slice(&tag[..out.len()]);
+
+        key.zeroize();
+        padded.zeroize();
+        state.zeroize();
+        tag.zeroize();
+
+        Ok(())
+    }
+
+    /// Checks the 128 bits `tag` against `msg`, in constant time.
+    ///
+    /// Fails with [`Error::AuthenticationFailed`] if they do not match, and with
+    /// [`Error::DataLimitExceeded`] if `msg` is longer than [`PRF_SHORT_MAX_LEN`] bytes.
+    pub fn verify(key: &Key, msg: &[u8], tag: &Tag) -> Result<(), Error> {
+        let mut expected = [0; 16];
+        Self::prf(key, msg, &mut expected)?;
+
+        let valid = Tag::from(expected) == *tag;
+        expected.zeroize();
+
+        if !valid {
+            return Err(Error::AuthenticationFailed);
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads the IV and the key, and applies the initial permutation.
+fn initialize(iv: u64, key: &Key) -> [u64; 5] {
+    let mut key = key_words(key);
+    let mut state = [round::encode(iv), key[0], key[1], 0, 0];
+    key.zeroize();
+    round::permute_encoded::<12>(&mut state);
+
+    state
+}
+
+/// Loads the key as two big-endian words.
+fn key_words(key: &Key) -> [u64; 2] {
+    let key = key.as_bytes();
+    [load(&key[0..8]), load(&key[8..16])]
+}
+
+/// Loads a big-endian word into the state, see [`round::encode`].
+fn load(bytes: &[u8]) -> u64 {
+    round::encode(u64::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+/// Stores a word of the state, undoing [`load`].
+fn store(word: u64) -> [u8; 8] {
+    round::decode(word).to_be_bytes()
+}
+
+/// Absorbs the whole `msg`, `MSG_RATE` bytes at a time.
+fn absorb(state: &mut [u64; 5], msg: &[u8]) {
+    let mut iter = msg.chunks_exact(MSG_RATE);
+    for c in iter.by_ref() {
+        xor_block(state, c);
+        round::permute_encoded::<12>(state);
+    }
+
+    // Pad the last block, which may be empty, with a single `1` bit.
+    let remainder = iter.remainder();
+    let mut last = [0; MSG_RATE];
+    last[..remainder.len()].copy_from_slice(remainder);
+    last[remainder.len()] = 0x80;
+    xor_block(state, &last);
+    last.zeroize();
+
+    // Domain separation of the last block.
+    state[4] ^= round::encode(1);
+    round::permute_encoded::<12>(state);
+}
+
+/// XORs a `MSG_RATE` bytes `block` into `x0..x3`.
+fn xor_block(state: &mut [u64; 5], block: &[u8]) {
+    for (s, c) in state[..MSG_RATE / 8].iter_mut().zip(block.chunks_exact(8)) {
+        *s ^= load(c);
+    }
+}
+
+/// Squeezes `out.len()` bytes, `OUT_RATE` bytes at a time.
+fn squeeze(state: &mut [u64; 5], out: &mut [u8]) {
+    let mut block = [0; OUT_RATE];
+    for (i, c) in out.chunks_mut(OUT_RATE).enumerate() {
+        if i > 0 {
+            round::permute_encoded::<12>(state);
+        }
+
+        block[0..8].copy_from_slice(&store(state[0]));
+        block[8..16].copy_from_slice(&store(state[1]));
+        c.copy_from_slice(&block[..c.len()]);
+    }
+    block.zeroize();
+}
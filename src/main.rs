@@ -1,14 +1,22 @@
-use anyhow::{bail, Context};
+use anyhow::Context;
 use ascon::aead128::AEAD128;
-use clap::{Parser, Subcommand};
-use std::fs;
-use std::path::PathBuf;
+use ascon::hash256::{Cxof128, Hash256, Xof128};
+use ascon::kat;
+use ascon::legacy_aead::{Ascon128, Ascon128a, Ascon80pq};
+use ascon::legacy_hash::{AsconHash, AsconHasha, AsconXof, AsconXofa};
+use ascon::mac::{AsconMac, AsconPrf, AsconPrfShort};
+use ascon::types::{Key, Nonce, Tag};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::str;
 
 #[derive(Debug, Parser)]
 #[command(version, about, long_about = None)]
 struct Args {
-    /// Turn debugging information on
+    /// Turn debugging information on (the steps of the library are traced with its `log` feature)
     #[arg(long)]
     debug: bool,
 
@@ -23,36 +31,329 @@ struct Args {
 enum Command {
     /// Performs `Ascon-AEAD128` (en/de)cryption.
     Aead {
-        ///
-        key: String,
-        ///
-        nonce: String,
-        ///
+        /// 128 bits key, as an hex string.
+        key: Key,
+        /// 128 bits nonce, as an hex string.
+        nonce: Nonce,
+        /// Associated data.
         ad: String,
-        ///
+        /// File to encrypt or decrypt.
         in_file: PathBuf,
 
         /// If given, means we are decrypting.
-        tag: Option<String>,
+        tag: Option<Tag>,
     },
+    /// Generates a known answer tests file in the NIST format.
+    ///
+    /// Keys, nonces and messages are `00 01 02 ...`. Every message length up to `--max-msg` is
+    /// used, and for each of them every AD (or customization for `cxof128`) length up to
+    /// `--max-ad`.
+    Kat {
+        algorithm: KatAlgorithm,
+        /// Maximum message length in bytes [default: 32 for the AEADs and `cxof128`, 16 for
+        /// `ascon-prf-short`, 1024 otherwise]
+        #[arg(long)]
+        max_msg: Option<usize>,
+        /// Maximum AD or customization length in bytes [default: 32]
+        #[arg(long)]
+        max_ad: Option<usize>,
+    },
+    /// Prints the `Ascon-Hash256` digest of files, in the same format as `sha256sum`.
+    ///
+    /// As there, a name holding a backslash or a newline is escaped, and its line starts with a
+    /// backslash.
+    ///
+    /// Exits with 0 if everything went fine, 1 if a checked file does not match its digest, and 2
+    /// if a file could not be read or a manifest line is malformed.
+    Hash {
+        /// Files to hash, or manifests with `--check`. Stdin is read for `-` or if none is given.
+        files: Vec<PathBuf>,
+        /// Reads `<hex>  <path>` lines from the files and checks that each path has this digest.
+        #[arg(short, long)]
+        check: bool,
+    },
+}
+
+/// Algorithms of the `kat` subcommand.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum KatAlgorithm {
+    Aead128,
+    Ascon128,
+    Ascon128a,
+    Ascon80pq,
+    Hash256,
+    Xof128,
+    Cxof128,
+    AsconHash,
+    AsconHasha,
+    AsconXof,
+    AsconXofa,
+    AsconMac,
+    AsconPrf,
+    AsconPrfShort,
 }
 
-fn parse_hex(hex_str: &str) -> anyhow::Result<Vec<u8>> {
-    if hex_str.len() % 2 != 0 {
-        bail!("Hex string length must be even");
+/// Exit code of the `hash` subcommand when a file does not match its digest.
+const EXIT_MISMATCH: u8 = 1;
+/// Exit code of the `hash` subcommand when a file can not be read, or a manifest is malformed.
+const EXIT_ERROR: u8 = 2;
+
+/// Opens `path` for reading, or stdin if it is `-`.
+fn open(path: &Path) -> io::Result<Box<dyn Read>> {
+    if path == Path::new("-") {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(File::open(path)?))
     }
+}
+
+/// Streams the content of `path` (see [`open`]) through `Ascon-Hash256`.
+fn hash_file(path: &Path) -> io::Result<[u8; 32]> {
+    log::debug!("hashing {}", path.display());
+    let mut reader = open(path)?;
+    let mut hash256 = Hash256::new();
+    let mut buffer = vec![0; 64 * 1024];
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(hash256.finalize()),
+            Ok(n) => hash256.update(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Escapes `name` as `sha256sum` does: `\`, newlines and carriage returns become `\\`, `\n` and
+/// `\r`.
+///
+/// Returns the prefix of the output line, `\` if anything was escaped, and the escaped name.
+fn escape(name: &str) -> (&'static str, String) {
+    if !name.contains(['\\', '\n', '\r']) {
+        return ("", name.to_owned());
+    }
+
+    let escaped = name
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    ("\\", escaped)
+}
+
+/// Undoes [`escape`], or returns `None` for an unknown escape sequence.
+fn unescape(name: &str) -> Option<String> {
+    let mut out = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        out.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+
+    Some(out)
+}
+
+/// Splits a `<hex>  <path>` manifest line, also accepting the ` *` separator of binary mode.
+///
+/// A line starting with `\` holds an escaped path, see [`escape`].
+fn parse_manifest_line(line: &str) -> Option<(&str, String)> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(line) => (true, line),
+        None => (false, line),
+    };
+
+    let (hex, path) = line.split_once(' ')?;
+    let path = path.strip_prefix(' ').or_else(|| path.strip_prefix('*'))?;
+
+    let valid = hex.len() == 64 && hex.bytes().all(|c| c.is_ascii_hexdigit());
+    if !valid || path.is_empty() {
+        return None;
+    }
+
+    let path = if escaped {
+        unescape(path)?
+    } else {
+        path.to_owned()
+    };
+    Some((hex, path))
+}
+
+/// Checks every file listed in `manifest`, and returns the exit code.
+fn check_manifest(manifest: &Path) -> u8 {
+    log::debug!("checking the files listed in {}", manifest.display());
+    let mut text = String::new();
+    if let Err(e) = open(manifest).and_then(|mut r| r.read_to_string(&mut text)) {
+        eprintln!("ascon: {}: {}", manifest.display(), e);
+        return EXIT_ERROR;
+    }
+
+    let mut status = 0;
+    let mut checked = 0;
+    for (i, line) in text.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        let Some((expected, path)) = parse_manifest_line(line) else {
+            eprintln!(
+                "ascon: {}:{}: improperly formatted line",
+                manifest.display(),
+                i + 1
+            );
+            status = status.max(EXIT_ERROR);
+            continue;
+        };
+        checked += 1;
+
+        let (prefix, name) = escape(&path);
+        match hash_file(Path::new(&path)) {
+            Ok(digest) if to_hex(&digest).eq_ignore_ascii_case(expected) => {
+                println!("{}{}: OK", prefix, name)
+            }
+            Ok(_) => {
+                println!("{}{}: FAILED", prefix, name);
+                status = status.max(EXIT_MISMATCH);
+            }
+            Err(e) => {
+                println!("{}{}: FAILED open or read", prefix, name);
+                eprintln!("ascon: {}: {}", name, e);
+                status = status.max(EXIT_ERROR);
+            }
+        }
+    }
+
+    if checked == 0 {
+        eprintln!("ascon: {}: no properly formatted lines", manifest.display());
+        status = EXIT_ERROR;
+    }
+
+    status
+}
+
+/// Returns `00 01 02 ...` on `len` bytes.
+fn sequence(len: usize) -> Vec<u8> {
+    (0..len).map(|i| i as u8).collect()
+}
+
+/// Generates the whole KAT file of `algorithm`, see [`Command::Kat`].
+fn generate_kat(
+    algorithm: KatAlgorithm,
+    max_msg: Option<usize>,
+    max_ad: Option<usize>,
+) -> anyhow::Result<String> {
+    use KatAlgorithm as A;
+
+    let mut out = String::new();
+    let mut count = 0;
+    let key: [u8; 20] = std::array::from_fn(|i| i as u8);
+    let key16: [u8; 16] = key[..16].try_into().unwrap();
+    let nonce = key16;
+
+    match algorithm {
+        A::Aead128 | A::Ascon128 | A::Ascon128a | A::Ascon80pq => {
+            // Only `Ascon-80pq` has a 160 bits key.
+            let key_bytes = match algorithm {
+                A::Ascon80pq => &key[..],
+                _ => &key16[..],
+            };
+
+            for plain in (0..=max_msg.unwrap_or(32)).map(sequence) {
+                for ad in (0..=max_ad.unwrap_or(32)).map(sequence) {
+                    let (mut cipher, tag) = match algorithm {
+                        A::Aead128 => AEAD128::encrypt(&key16.into(), &nonce.into(), &ad, &plain),
+                        A::Ascon128 => Ascon128::encrypt(&key16.into(), &nonce.into(), &ad, &plain),
+                        A::Ascon128a => {
+                            Ascon128a::encrypt(&key16.into(), &nonce.into(), &ad, &plain)
+                        }
+                        _ => Ascon80pq::encrypt(&key.into(), &nonce.into(), &ad, &plain),
+                    };
+                    cipher.extend(tag.as_bytes());
+
+                    count += 1;
+                    kat::write_record(
+                        &mut out,
+                        count,
+                        &[
+                            ("Key", key_bytes),
+                            ("Nonce", &nonce),
+                            ("PT", &plain),
+                            ("AD", &ad),
+                            ("CT", &cipher),
+                        ],
+                    )?;
+                }
+            }
+        }
+        A::Cxof128 => {
+            for msg in (0..=max_msg.unwrap_or(32)).map(sequence) {
+                for z in (0..=max_ad.unwrap_or(32)).map(sequence) {
+                    let mut digest = [0; 32];
+                    Cxof128::xof(&z, &msg)?.squeeze(&mut digest);
+
+                    count += 1;
+                    kat::write_record(
+                        &mut out,
+                        count,
+                        &[("Msg", &msg), ("Z", &z), ("MD", &digest)],
+                    )?;
+                }
+            }
+        }
+        A::Hash256 | A::Xof128 | A::AsconHash | A::AsconHasha | A::AsconXof | A::AsconXofa => {
+            for msg in (0..=max_msg.unwrap_or(1024)).map(sequence) {
+                let mut digest = [0; 32];
+                match algorithm {
+                    A::Hash256 => digest = Hash256::hash(&msg),
+                    A::Xof128 => Xof128::xof(&msg).squeeze(&mut digest),
+                    A::AsconHash => digest = AsconHash::hash(&msg),
+                    A::AsconHasha => digest = AsconHasha::hash(&msg),
+                    A::AsconXof => AsconXof::xof(&msg, &mut digest),
+                    _ => AsconXofa::xof(&msg, &mut digest),
+                }
+
+                count += 1;
+                kat::write_record(&mut out, count, &[("Msg", &msg), ("MD", &digest)])?;
+            }
+        }
+        A::AsconMac | A::AsconPrf | A::AsconPrfShort => {
+            let max_msg = match algorithm {
+                A::AsconPrfShort => max_msg.unwrap_or(16),
+                _ => max_msg.unwrap_or(1024),
+            };
 
-    match (0..hex_str.len())
-        .step_by(2)
-        .map(|i| u8::from_str_radix(&hex_str[i..i + 2], 16))
-        .collect()
-    {
-        Ok(v) => Ok(v),
-        Err(_) => bail!("Invalid hex character."),
+            for msg in (0..=max_msg).map(sequence) {
+                let mut tag = [0; 16];
+                match algorithm {
+                    A::AsconMac => tag = *AsconMac::mac(&key16.into(), &msg).as_bytes(),
+                    A::AsconPrf => AsconPrf::prf(&key16.into(), &msg, &mut tag),
+                    _ => AsconPrfShort::prf(&key16.into(), &msg, &mut tag)
+                        .context(format!("Ascon-PrfShort of {} bytes", msg.len()))?,
+                }
+
+                count += 1;
+                kat::write_record(
+                    &mut out,
+                    count,
+                    &[("Key", &key16), ("Msg", &msg), ("Tag", &tag)],
+                )?;
+            }
+        }
     }
+
+    Ok(out)
 }
 
-fn main() -> anyhow::Result<()> {
+fn main() -> anyhow::Result<ExitCode> {
     let args = Args::parse();
 
     if args.debug {
@@ -62,6 +363,32 @@ fn main() -> anyhow::Result<()> {
     pretty_env_logger::init();
 
     match args.cmd {
+        Command::Hash { mut files, check } => {
+            if files.is_empty() {
+                files.push(PathBuf::from("-"));
+            }
+
+            let mut status = 0;
+            for path in &files {
+                if check {
+                    status = status.max(check_manifest(path));
+                    continue;
+                }
+
+                match hash_file(path) {
+                    Ok(digest) => {
+                        let (prefix, name) = escape(&path.to_string_lossy());
+                        println!("{}{}  {}", prefix, to_hex(&digest), name)
+                    }
+                    Err(e) => {
+                        eprintln!("ascon: {}: {}", path.display(), e);
+                        status = EXIT_ERROR;
+                    }
+                }
+            }
+
+            return Ok(ExitCode::from(status));
+        }
         Command::Aead {
             key,
             nonce,
@@ -69,26 +396,14 @@ fn main() -> anyhow::Result<()> {
             in_file,
             tag,
         } => {
-            let key = parse_hex(&key).context("Parsing key hex.")?;
-            anyhow::ensure!(key.len() == 16, "Key must be exactly 16 bytes long.");
-            let key: [u8; 16] = key.try_into().unwrap();
-            let nonce = parse_hex(&nonce).context("Parsing key hex.")?;
-            anyhow::ensure!(nonce.len() == 16, "Nonce must be exactly 16 bytes long.");
-            let nonce: [u8; 16] = nonce.try_into().unwrap();
-
             let ad = ad.as_bytes();
 
             let input = fs::read(&in_file).context(format!("Reading [{}]", in_file.display()))?;
 
             match tag {
                 Some(tag) => {
-                    let tag = parse_hex(&tag).context("Parsing tag.")?;
-                    anyhow::ensure!(tag.len() == 16, "Tag must be exactly 16 bytes long.");
-                    let tag: [u8; 16] = tag.try_into().unwrap();
-
-                    let Some(dec) = AEAD128::decrypt(key, nonce, &ad, &input, tag) else {
-                        bail!("Failed to decrypt [{}], invalid.tag.", in_file.display());
-                    };
+                    let dec = AEAD128::decrypt(&key, &nonce, ad, &input, &tag)
+                        .context(format!("Decrypting [{}]", in_file.display()))?;
                     if let Some(out_path) = args.out_path {
                         fs::write(&out_path, dec)
                             .context(format!("Writing output to [{}]", out_path.display()))?;
@@ -107,7 +422,7 @@ fn main() -> anyhow::Result<()> {
                     };
                 }
                 None => {
-                    let (enc, tag) = AEAD128::encrypt(key, nonce, ad, &input);
+                    let (enc, tag) = AEAD128::encrypt(&key, &nonce, ad, &input);
 
                     if let Some(out_path) = args.out_path {
                         fs::write(&out_path, enc)
@@ -126,17 +441,25 @@ fn main() -> anyhow::Result<()> {
                         }
                     };
 
-                    println!(
-                        "Encrypted tag: [{}]",
-                        tag.iter()
-                            .map(|b| format!("{:02x}", b))
-                            .collect::<Vec<String>>()
-                            .join("")
-                    );
+                    println!("Encrypted tag: [{}]", tag);
                 }
             }
         }
+        Command::Kat {
+            algorithm,
+            max_msg,
+            max_ad,
+        } => {
+            let kat = generate_kat(algorithm, max_msg, max_ad)?;
+
+            if let Some(out_path) = args.out_path {
+                fs::write(&out_path, kat)
+                    .context(format!("Writing output to [{}]", out_path.display()))?;
+            } else {
+                print!("{}", kat);
+            }
+        }
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
@@ -0,0 +1,151 @@
+//! Public access to the `Ascon` permutation, as a building block for experiments.
+//!
+//! The modes of this crate only ever apply the last 6, 8 or 12 rounds of the permutation. For
+//! cryptanalysis, [`Permutation`] can apply any range of the 12 rounds, or single rounds with a
+//! chosen constant, and undo them, on a [`State`] which can be loaded from and stored to bytes.
+//!
+//! ```
+//! use ascon::permutation::{Permutation, State};
+//!
+//! // Rounds 3 to 7 of the 12 rounds schedule.
+//! let mut p = Permutation::new(State::from_bytes(&[0; State::LEN]));
+//! p.apply_rounds(3, 5)?;
+//!
+//! // Is the same as rounds 3 to 5, followed by rounds 6 and 7.
+//! let mut q = Permutation::new(State::default());
+//! q.apply_rounds(3, 3)?;
+//! q.round_with_constant(0x96);
+//! q.round_with_constant(0x87);
+//!
+//! assert_eq!(p.state(), q.state());
+//! # Ok::<(), ascon::Error>(())
+//! ```
+
+use core::fmt;
+
+use crate::{round, Error};
+
+/// The 320 bits state of the permutation, as 5 words `x0..x4`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct State([u64; 5]);
+
+impl State {
+    /// Length in bytes.
+    pub const LEN: usize = 40;
+
+    pub fn new(words: [u64; 5]) -> Self {
+        Self(words)
+    }
+
+    pub fn words(&self) -> &[u64; 5] {
+        &self.0
+    }
+
+    pub fn words_mut(&mut self) -> &mut [u64; 5] {
+        &mut self.0
+    }
+
+    /// Loads the state from its bytes: each word is read in little-endian order, as in
+    /// `SP 800-232`.
+    pub fn from_bytes(bytes: &[u8; Self::LEN]) -> Self {
+        let mut words = [0; 5];
+        for (w, b) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+            *w = u64::from_le_bytes(b.try_into().unwrap());
+        }
+
+        Self(words)
+    }
+
+    /// Stores the state as bytes, the inverse of [`State::from_bytes`].
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0; Self::LEN];
+        for (b, w) in bytes.chunks_exact_mut(8).zip(self.0) {
+            b.copy_from_slice(&w.to_le_bytes());
+        }
+
+        bytes
+    }
+}
+
+impl From<[u64; 5]> for State {
+    fn from(words: [u64; 5]) -> Self {
+        Self(words)
+    }
+}
+
+impl From<State> for [u64; 5] {
+    fn from(state: State) -> Self {
+        state.0
+    }
+}
+
+impl fmt::Display for State {
+    /// Shows the 5 words in hex, as [`round::state_to_str`] does.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x0, x1, x2, x3, x4] = self.0;
+        write!(
+            f,
+            "x0=0x{:x} x1=0x{:x} x2=0x{:x} x3=0x{:x} x4=0x{:x}",
+            x0, x1, x2, x3, x4
+        )
+    }
+}
+
+/// Applies rounds of the permutation to the [`State`] it holds.
+///
+/// Rounds are numbered from 0 to 11 as in the full 12 rounds schedule: the `n` rounds permutation
+/// used by the modes is rounds `12 - n` to 11.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Permutation {
+    state: State,
+}
+
+impl Permutation {
+    /// Number of rounds of the full schedule.
+    pub const ROUNDS: usize = 12;
+
+    pub fn new(state: State) -> Self {
+        Self { state }
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut State {
+        &mut self.state
+    }
+
+    pub fn into_state(self) -> State {
+        self.state
+    }
+
+    /// Applies `count` rounds, starting with round `start`, each with its own round constant.
+    ///
+    /// Fails with [`Error::InvalidRounds`], leaving the state untouched, if the rounds go past the
+    /// last one, i.e. `start + count > 12`.
+    pub fn apply_rounds(&mut self, start: usize, count: usize) -> Result<(), Error> {
+        for &c in round::schedule(start, count)? {
+            round::round(&mut self.state.0, c);
+        }
+
+        Ok(())
+    }
+
+    /// Undoes [`Permutation::apply_rounds`] with the same arguments.
+    ///
+    /// Fails with [`Error::InvalidRounds`] in the same cases.
+    pub fn inverse_rounds(&mut self, start: usize, count: usize) -> Result<(), Error> {
+        round::inverse_rounds(&mut self.state.0, start, count)
+    }
+
+    /// Applies a single round, with `constant` added to `x2` instead of the scheduled one.
+    pub fn round_with_constant(&mut self, constant: u64) {
+        round::round(&mut self.state.0, constant);
+    }
+
+    /// Undoes [`Permutation::round_with_constant`] with the same `constant`.
+    pub fn inverse_round_with_constant(&mut self, constant: u64) {
+        round::inverse_round(&mut self.state.0, constant);
+    }
+}
@@ -0,0 +1,246 @@
+//! Slow implementation following the pseudocode of `SP 800-232` literally, to test the optimized
+//! modules against.
+//!
+//! Everything works on bit strings: the S-box is the table of the standard applied one column at
+//! a time, and messages are padded bit by bit. Bit `i` of a byte string is bit `i % 8` of its byte
+//! `i / 8`, and bit `i` of the state is bit `i % 64` of its word `S_(i / 64)`.
+//!
+//! It is only built for the tests, or with the `reference` feature.
+
+use alloc::vec::Vec;
+
+/// Round constants `c_0` to `c_15` (Table 5).
+const CONSTANTS: [u64; 16] = [
+    0x3c, 0x2d, 0x1e, 0x0f, 0xf0, 0xe1, 0xd2, 0xc3, 0xb4, 0xa5, 0x96, 0x87, 0x78, 0x69, 0x5a, 0x4b,
+];
+
+/// The 5 bits S-box (Table 6).
+const SBOX: [u8; 32] = [
+    0x04, 0x0b, 0x1f, 0x14, 0x1a, 0x15, 0x09, 0x02, 0x1b, 0x05, 0x08, 0x12, 0x1d, 0x03, 0x06, 0x1c,
+    0x1e, 0x13, 0x07, 0x0e, 0x00, 0x0d, 0x11, 0x18, 0x10, 0x0c, 0x01, 0x19, 0x16, 0x0a, 0x0f, 0x17,
+];
+
+const AEAD128_IV: u64 = 0x00001000808c0001;
+const HASH256_IV: u64 = 0x0000080100cc0002;
+
+/// The 320 bits state `S_0 || S_1 || S_2 || S_3 || S_4`.
+struct State([u64; 5]);
+
+impl State {
+    fn bit(&self, i: usize) -> bool {
+        (self.0[i / 64] >> (i % 64)) & 1 == 1
+    }
+
+    fn flip(&mut self, i: usize) {
+        self.0[i / 64] ^= 1 << (i % 64);
+    }
+
+    /// `S[start : start + |bits|] ^= bits`
+    fn xor(&mut self, start: usize, bits: &[bool]) {
+        for (i, &b) in bits.iter().enumerate() {
+            if b {
+                self.flip(start + i);
+            }
+        }
+    }
+
+    /// `S[start : start + |bits|] = bits`
+    fn set(&mut self, start: usize, bits: &[bool]) {
+        for (i, &b) in bits.iter().enumerate() {
+            if self.bit(start + i) != b {
+                self.flip(start + i);
+            }
+        }
+    }
+
+    /// `S[start : start + len]`
+    fn bits(&self, start: usize, len: usize) -> Vec<bool> {
+        (start..start + len).map(|i| self.bit(i)).collect()
+    }
+
+    /// `Ascon-p[rnd]` (Algorithm 1).
+    fn permute(&mut self, rnd: usize) {
+        for i in 0..rnd {
+            self.constant_addition(CONSTANTS[16 - rnd + i]);
+            self.substitution();
+            self.linear_diffusion();
+        }
+    }
+
+    /// `p_C`
+    fn constant_addition(&mut self, c: u64) {
+        self.0[2] ^= c;
+    }
+
+    /// `p_S`: the S-box is applied to each column `S_0,j || S_1,j || S_2,j || S_3,j || S_4,j`,
+    /// `S_0,j` being the most significant bit.
+    fn substitution(&mut self) {
+        for j in 0..64 {
+            let mut x = 0;
+            for word in 0..5 {
+                x = x << 1 | ((self.0[word] >> j) & 1) as usize;
+            }
+
+            let y = SBOX[x];
+            for word in 0..5 {
+                let bit = u64::from(y >> (4 - word) & 1);
+                self.0[word] = self.0[word] & !(1 << j) | bit << j;
+            }
+        }
+    }
+
+    /// `p_L`
+    fn linear_diffusion(&mut self) {
+        let s = &mut self.0;
+        s[0] ^= s[0].rotate_right(19) ^ s[0].rotate_right(28);
+        s[1] ^= s[1].rotate_right(61) ^ s[1].rotate_right(39);
+        s[2] ^= s[2].rotate_right(1) ^ s[2].rotate_right(6);
+        s[3] ^= s[3].rotate_right(10) ^ s[3].rotate_right(17);
+        s[4] ^= s[4].rotate_right(7) ^ s[4].rotate_right(41);
+    }
+}
+
+fn to_bits(bytes: &[u8]) -> Vec<bool> {
+    (0..8 * bytes.len())
+        .map(|i| (bytes[i / 8] >> (i % 8)) & 1 == 1)
+        .collect()
+}
+
+fn from_bits(bits: &[bool]) -> Vec<u8> {
+    let mut bytes = alloc::vec![0; bits.len().div_ceil(8)];
+    for (i, &b) in bits.iter().enumerate() {
+        bytes[i / 8] |= u8::from(b) << (i % 8);
+    }
+
+    bytes
+}
+
+/// `pad(X, r)`: appends a single 1 and as few 0s as possible to make the length of `X` a multiple
+/// of `r`.
+fn pad(mut x: Vec<bool>, r: usize) -> Vec<bool> {
+    x.push(true);
+    while !x.len().is_multiple_of(r) {
+        x.push(false);
+    }
+
+    x
+}
+
+/// Initialization of `Ascon-AEAD128`: `S = IV || K || N`, then the key is XORed again.
+fn aead128_initialize(k: &[bool], nonce: &[u8; 16]) -> State {
+    let mut s = State([AEAD128_IV, 0, 0, 0, 0]);
+    s.xor(64, k);
+    s.xor(192, &to_bits(nonce));
+    s.permute(12);
+    s.xor(192, k);
+
+    s
+}
+
+/// Absorbs the associated data, and flips the last bit of the state.
+fn aead128_associated_data(s: &mut State, ad: &[u8]) {
+    if !ad.is_empty() {
+        for block in pad(to_bits(ad), 128).chunks(128) {
+            s.xor(0, block);
+            s.permute(8);
+        }
+    }
+    s.flip(319);
+}
+
+/// Finalization of `Ascon-AEAD128`, returning the tag.
+fn aead128_finalize(mut s: State, k: &[bool]) -> [u8; 16] {
+    s.xor(128, k);
+    s.permute(12);
+    let mut t = s.bits(192, 128);
+    for (t, k) in t.iter_mut().zip(k) {
+        *t ^= k;
+    }
+
+    from_bits(&t).try_into().unwrap()
+}
+
+/// `Ascon-AEAD128.enc(K, N, A, P)` (Algorithm 3), returning the ciphertext and the tag.
+pub fn aead128_encrypt(
+    key: &[u8; 16],
+    nonce: &[u8; 16],
+    ad: &[u8],
+    plain: &[u8],
+) -> (Vec<u8>, [u8; 16]) {
+    let k = to_bits(key);
+    let mut s = aead128_initialize(&k, nonce);
+    aead128_associated_data(&mut s, ad);
+
+    // Plaintext: every full block, then the last one which may be empty.
+    let p = to_bits(plain);
+    let full = p.len() / 128 * 128;
+    let mut c = Vec::new();
+    for block in p[..full].chunks(128) {
+        s.xor(0, block);
+        c.extend(s.bits(0, 128));
+        s.permute(8);
+    }
+
+    let last = &p[full..];
+    s.xor(0, last);
+    c.extend(s.bits(0, last.len()));
+    s.flip(last.len());
+
+    (from_bits(&c), aead128_finalize(s, &k))
+}
+
+/// `Ascon-AEAD128.dec(K, N, A, C, T)` (Algorithm 4), returning the plaintext, or `None` for
+/// `fail`.
+pub fn aead128_decrypt(
+    key: &[u8; 16],
+    nonce: &[u8; 16],
+    ad: &[u8],
+    cipher: &[u8],
+    tag: &[u8; 16],
+) -> Option<Vec<u8>> {
+    let k = to_bits(key);
+    let mut s = aead128_initialize(&k, nonce);
+    aead128_associated_data(&mut s, ad);
+
+    // Ciphertext: every full block, then the last one which may be empty.
+    let c = to_bits(cipher);
+    let full = c.len() / 128 * 128;
+    let mut p = Vec::new();
+    for block in c[..full].chunks(128) {
+        p.extend(s.bits(0, 128).iter().zip(block).map(|(s, c)| s ^ c));
+        s.set(0, block);
+        s.permute(8);
+    }
+
+    // `S[0 : l] = C~`, then the padding bit right after it.
+    let last = &c[full..];
+    p.extend(s.bits(0, last.len()).iter().zip(last).map(|(s, c)| s ^ c));
+    s.set(0, last);
+    s.flip(last.len());
+
+    (aead128_finalize(s, &k) == *tag).then(|| from_bits(&p))
+}
+
+/// `Ascon-Hash256(M)` (Algorithm 5).
+pub fn hash256(message: &[u8]) -> [u8; 32] {
+    // Initialization
+    let mut s = State([HASH256_IV, 0, 0, 0, 0]);
+    s.permute(12);
+
+    // Absorbing
+    for block in pad(to_bits(message), 64).chunks(64) {
+        s.xor(0, block);
+        s.permute(12);
+    }
+
+    // Squeezing
+    let mut h = Vec::new();
+    for i in 0..4 {
+        if i > 0 {
+            s.permute(12);
+        }
+        h.extend(s.bits(0, 64));
+    }
+
+    from_bits(&h).try_into().unwrap()
+}
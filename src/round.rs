@@ -1,7 +1,19 @@
-use log::debug;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+
+use crate::Error;
+
+pub(crate) mod interleaved;
+#[cfg(feature = "alloc")]
+pub(crate) mod lanes;
+
+/// Whether the modes use the bit-interleaved implementation, enabled by the `interleaved` feature.
+///
+/// Their state then stays interleaved from the initialization to the output, see [`encode`].
+const INTERLEAVED: bool = cfg!(feature = "interleaved");
 
 /// These are the round constants used in the `Ascon` permutation.
-const ROUND_CONSTANTS: [u64; 16] = [
+pub(crate) const ROUND_CONSTANTS: [u64; 16] = [
     // These 4 round constants are never used because we only go up to 12 rounds max:
     0x000000000000003c,
     0x000000000000002d,
@@ -23,6 +35,92 @@ const ROUND_CONSTANTS: [u64; 16] = [
 ];
 
 pub fn do_n_rounds(state: &mut [u64; 5], n: usize) {
+    if INTERLEAVED {
+        interleaved::do_n_rounds(state, n);
+    } else {
+        do_n_rounds_64(state, n);
+    }
+}
+
+/// Applies the last `N` rounds of the permutation, as [`do_n_rounds`] does.
+///
+/// `N` must be between 1 and 16, which is checked at compile time:
+///
+/// ```compile_fail
+/// ascon::round::permute::<17>(&mut [0; 5]);
+/// ```
+///
+/// The rounds are fully unrolled, with their constants folded in.
+#[inline]
+pub fn permute<const N: usize>(state: &mut [u64; 5]) {
+    const { assert!(N >= 1 && N <= 16, "Invalid number of rounds") };
+
+    if INTERLEAVED {
+        interleaved::do_n_rounds(state, N);
+        return;
+    }
+
+    // Only the `N` last rounds are kept, each test being resolved at compile time.
+    macro_rules! unroll {
+        ($($i:literal)*) => {
+            $(
+                if 16 - $i <= N {
+                    round(state, ROUND_CONSTANTS[$i]);
+                }
+            )*
+        };
+    }
+    unroll!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15);
+}
+
+/// Converts a word into the form the modes keep their state in: bit-interleaved with the
+/// `interleaved` feature, unchanged otherwise.
+///
+/// The modes convert every word they load, and every constant they XOR into their state. They then
+/// permute it with [`permute_encoded`], and [`decode`] the words they output.
+#[inline(always)]
+pub(crate) const fn encode(x: u64) -> u64 {
+    if INTERLEAVED {
+        interleaved::encode(x)
+    } else {
+        x
+    }
+}
+
+/// Undoes [`encode`].
+#[inline(always)]
+pub(crate) const fn decode(x: u64) -> u64 {
+    if INTERLEAVED {
+        interleaved::decode(x)
+    } else {
+        x
+    }
+}
+
+/// Same as [`permute`], on a state converted with [`encode`].
+///
+/// Unlike [`permute`] and [`do_n_rounds`], it never converts the state.
+#[inline]
+pub(crate) fn permute_encoded<const N: usize>(state: &mut [u64; 5]) {
+    if INTERLEAVED {
+        interleaved::do_n_rounds_encoded(state, N);
+    } else {
+        permute::<N>(state);
+    }
+}
+
+/// Same as [`do_n_rounds`], on a state converted with [`encode`].
+#[cfg(feature = "alloc")]
+pub(crate) fn do_n_rounds_encoded(state: &mut [u64; 5], n: usize) {
+    if INTERLEAVED {
+        interleaved::do_n_rounds_encoded(state, n);
+    } else {
+        do_n_rounds_64(state, n);
+    }
+}
+
+/// Same as [`do_n_rounds`], working on the 64 bits words directly.
+pub(crate) fn do_n_rounds_64(state: &mut [u64; 5], n: usize) {
     for i in 0..n {
         round(state, ROUND_CONSTANTS[16 - n + i]);
     }
@@ -31,7 +129,8 @@ pub fn do_n_rounds(state: &mut [u64; 5], n: usize) {
 /// This is the round function of `Ascon`.
 ///
 /// (TODO: Write about the number of temp variables used.)
-fn round(state: &mut [u64; 5], round_constant: u64) {
+#[inline(always)]
+pub(crate) fn round(state: &mut [u64; 5], round_constant: u64) {
     // Constant Addition Layer
     state[2] ^= round_constant;
 
@@ -58,14 +157,91 @@ fn round(state: &mut [u64; 5], round_constant: u64) {
     state[2] = t2 ^ t2.rotate_right(1) ^ t2.rotate_right(6);
     state[3] = t3 ^ t3.rotate_right(10) ^ t3.rotate_right(17);
     state[4] = t4 ^ t4.rotate_right(7) ^ t4.rotate_right(41);
+}
+
+/// Returns the constants of `count` rounds starting with round `start`, the rounds being numbered
+/// from 0 to 11 as in the full 12 rounds schedule.
+///
+/// Fails with [`Error::InvalidRounds`] if the rounds go past the last one, i.e.
+/// `start + count > 12`.
+pub(crate) fn schedule(start: usize, count: usize) -> Result<&'static [u64], Error> {
+    if start.checked_add(count).is_none_or(|end| end > 12) {
+        return Err(Error::InvalidRounds { start, count });
+    }
+
+    // The 12 rounds use the last 12 constants.
+    Ok(&ROUND_CONSTANTS[4..][start..start + count])
+}
+
+/// The 5 bits S-box, `x0` giving the most significant bit of its input and output.
+const SBOX: [u8; 32] = [
+    0x04, 0x0b, 0x1f, 0x14, 0x1a, 0x15, 0x09, 0x02, 0x1b, 0x05, 0x08, 0x12, 0x1d, 0x03, 0x06, 0x1c,
+    0x1e, 0x13, 0x07, 0x0e, 0x00, 0x0d, 0x11, 0x18, 0x10, 0x0c, 0x01, 0x19, 0x16, 0x0a, 0x0f, 0x17,
+];
+
+/// The inverse of [`SBOX`].
+const INVERSE_SBOX: [u8; 32] = {
+    let mut inverse = [0; 32];
+    let mut i = 0;
+    while i < 32 {
+        inverse[SBOX[i] as usize] = i as u8;
+        i += 1;
+    }
+    inverse
+};
+
+/// The two right rotations of each word in the linear diffusion layer.
+const ROTATIONS: [(u32, u32); 5] = [(19, 28), (61, 39), (1, 6), (10, 17), (7, 41)];
+
+/// This is the inverse of a round, as applied by
+/// [`crate::permutation::Permutation::round_with_constant`] with the same `round_constant`.
+///
+/// It is much slower than a round, as the S-box is inverted one column at a time.
+pub fn inverse_round(state: &mut [u64; 5], round_constant: u64) {
+    // Linear Diffusion Layer
+    //
+    // Each word goes through `L(x) = x ^ (x >>> a) ^ (x >>> b)`. Seen as a polynomial in the
+    // rotation, squaring `L` doubles its rotations, and `L^64` is the identity as rotations are
+    // modulo 64: the inverse `L^63` is the product of `L^(2^i)` for `i` from 0 to 5.
+    for (x, &(a, b)) in state.iter_mut().zip(&ROTATIONS) {
+        for i in 0..6 {
+            *x ^= x.rotate_right((a << i) % 64) ^ x.rotate_right((b << i) % 64);
+        }
+    }
+
+    // Substitution Layer (S-Box)
+    let mut words = [0; 5];
+    for bit in 0..64 {
+        let column = state
+            .iter()
+            .fold(0, |acc, x| acc << 1 | ((x >> bit) & 1) as usize);
+        let column = INVERSE_SBOX[column];
+
+        for (j, w) in words.iter_mut().enumerate() {
+            *w |= u64::from((column >> (4 - j)) & 1) << bit;
+        }
+    }
+    *state = words;
+
+    // Constant Addition Layer
+    state[2] ^= round_constant;
+}
+
+/// Undoes `count` rounds starting with round `start`, the rounds being numbered from 0 to 11 as in
+/// the full 12 rounds schedule: rounds `start + count - 1` down to `start` are inverted.
+///
+/// Fails with [`Error::InvalidRounds`], leaving `state` untouched, if the rounds go past the last
+/// one, i.e. `start + count > 12`.
+pub fn inverse_rounds(state: &mut [u64; 5], start: usize, count: usize) -> Result<(), Error> {
+    for &c in schedule(start, count)?.iter().rev() {
+        inverse_round(state, c);
+    }
 
-    debug!("   round output: {}", state_to_str(state));
+    Ok(())
 }
 
-/// Displays the internal 5 word state.
+/// Displays the internal 5 word state, as [`State`](crate::permutation::State) does.
+#[cfg(feature = "alloc")]
 pub fn state_to_str(state: &[u64; 5]) -> String {
-    format!(
-        "x0=0x{:x} x1=0x{:x} x2=0x{:x} x3=0x{:x} x4=0x{:x}",
-        state[0], state[1], state[2], state[3], state[4]
-    )
+    crate::permutation::State::new(*state).to_string()
 }
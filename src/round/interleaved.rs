@@ -0,0 +1,146 @@
+//! Bit-interleaved implementation of the permutation, for 32 bits targets.
+//!
+//! Each 64 bits word is split in two `u32` halves: one holding its even bits, the other one its
+//! odd bits. A 64 bits rotation then boils down to two 32 bits rotations, instead of the shifts and
+//! ORs a 32 bits CPU needs to emulate it.
+//!
+//! The modes keep their state in this form between two permutations (see [`super::encode`]), so
+//! that only the words they load and store have to be converted.
+
+use super::ROUND_CONSTANTS;
+
+/// The 320 bits state, as the even and the odd halves of its 5 words.
+struct State {
+    even: [u32; 5],
+    odd: [u32; 5],
+}
+
+impl State {
+    /// Splits words already converted with [`encode`], which is free on 32 bits targets.
+    fn from_encoded(state: &[u64; 5]) -> Self {
+        Self {
+            even: state.map(|x| x as u32),
+            odd: state.map(|x| (x >> 32) as u32),
+        }
+    }
+
+    fn to_encoded(&self) -> [u64; 5] {
+        core::array::from_fn(|i| (self.odd[i] as u64) << 32 | self.even[i] as u64)
+    }
+
+    /// This is the round function of `Ascon`, see [`super::round`].
+    fn round(&mut self, round_constant: (u32, u32)) {
+        // Constant Addition Layer
+        self.even[2] ^= round_constant.0;
+        self.odd[2] ^= round_constant.1;
+
+        // Substitution Layer (S-Box), which is bitsliced so it works the same on both halves.
+        sbox(&mut self.even);
+        sbox(&mut self.odd);
+
+        // Linear Diffusion Layer
+        for (i, (r0, r1)) in [(19, 28), (61, 39), (1, 6), (10, 17), (7, 41)]
+            .into_iter()
+            .enumerate()
+        {
+            let t = (self.even[i], self.odd[i]);
+            let a = rotate_right(t, r0);
+            let b = rotate_right(t, r1);
+            self.even[i] = t.0 ^ a.0 ^ b.0;
+            self.odd[i] = t.1 ^ a.1 ^ b.1;
+        }
+    }
+}
+
+/// Rotates the `(even, odd)` halves of a word as the whole word would be rotated by `n` bits to the
+/// right.
+#[inline(always)]
+fn rotate_right((even, odd): (u32, u32), n: u32) -> (u32, u32) {
+    if n.is_multiple_of(2) {
+        (even.rotate_right(n / 2), odd.rotate_right(n / 2))
+    } else {
+        // Odd bits move to even positions, and the other way around.
+        (odd.rotate_right(n / 2), even.rotate_right(n / 2 + 1))
+    }
+}
+
+/// The S-box of `Ascon`, applied on 32 bits slices.
+#[inline(always)]
+fn sbox(x: &mut [u32; 5]) {
+    x[0] ^= x[4];
+    x[2] ^= x[1];
+    x[4] ^= x[3];
+
+    let mut t0 = x[0] ^ (!x[1] & x[2]);
+    let mut t1 = x[1] ^ (!x[2] & x[3]);
+    let mut t2 = x[2] ^ (!x[3] & x[4]);
+    let mut t3 = x[3] ^ (!x[4] & x[0]);
+    let t4 = x[4] ^ (!x[0] & x[1]);
+
+    t1 ^= t0;
+    t0 ^= t4;
+    t3 ^= t2;
+    t2 = !t2;
+
+    *x = [t0, t1, t2, t3, t4];
+}
+
+/// Swaps the bits of `x` selected by `mask` with the ones `shift` positions higher.
+const fn delta_swap(x: u64, mask: u64, shift: u32) -> u64 {
+    let t = (x ^ (x >> shift)) & mask;
+    x ^ t ^ (t << shift)
+}
+
+/// Moves the even bits of `x` to its low half, and its odd bits to its high half.
+pub(crate) const fn encode(mut x: u64) -> u64 {
+    x = delta_swap(x, 0x2222222222222222, 1);
+    x = delta_swap(x, 0x0c0c0c0c0c0c0c0c, 2);
+    x = delta_swap(x, 0x00f000f000f000f0, 4);
+    x = delta_swap(x, 0x0000ff000000ff00, 8);
+    x = delta_swap(x, 0x00000000ffff0000, 16);
+
+    x
+}
+
+/// Undoes [`encode`].
+pub(crate) const fn decode(mut x: u64) -> u64 {
+    // Every swap is its own inverse, so they only have to be done in the reverse order.
+    x = delta_swap(x, 0x00000000ffff0000, 16);
+    x = delta_swap(x, 0x0000ff000000ff00, 8);
+    x = delta_swap(x, 0x00f000f000f000f0, 4);
+    x = delta_swap(x, 0x0c0c0c0c0c0c0c0c, 2);
+    x = delta_swap(x, 0x2222222222222222, 1);
+
+    x
+}
+
+/// [`ROUND_CONSTANTS`], already interleaved.
+const INTERLEAVED_CONSTANTS: [(u32, u32); 16] = {
+    let mut out = [(0, 0); 16];
+    let mut i = 0;
+    while i < 16 {
+        let x = encode(ROUND_CONSTANTS[i]);
+        out[i] = (x as u32, (x >> 32) as u32);
+        i += 1;
+    }
+
+    out
+};
+
+/// Same as [`super::do_n_rounds`].
+pub(crate) fn do_n_rounds(state: &mut [u64; 5], n: usize) {
+    let mut encoded = state.map(encode);
+    do_n_rounds_encoded(&mut encoded, n);
+
+    *state = encoded.map(decode);
+}
+
+/// Same as [`do_n_rounds`], on a state already converted with [`encode`].
+pub(crate) fn do_n_rounds_encoded(state: &mut [u64; 5], n: usize) {
+    let mut s = State::from_encoded(state);
+    for i in 0..n {
+        s.round(INTERLEAVED_CONSTANTS[16 - n + i]);
+    }
+
+    *state = s.to_encoded();
+}
@@ -0,0 +1,261 @@
+//! Runs 4 independent permutations at once, with SIMD instructions when they are available.
+//!
+//! This is meant for batches of small messages, where a single state leaves most of the CPU idle.
+//! [`super::do_n_rounds`] stays the reference: every path here must give the exact same states.
+//!
+//! The states are the ones of the modes, i.e. converted with [`super::encode`]. The SIMD paths
+//! work on plain words, so they are left out with the `interleaved` feature.
+
+use crate::utils::Zeroize;
+
+/// Number of states permuted together.
+pub(crate) const LANES: usize = 4;
+
+/// A computation which alternates between updating its state and permuting it, so that several of
+/// them can share the multi-lane permutation.
+pub(crate) trait LaneJob {
+    /// Returns the state to start from.
+    fn start(&self) -> [u64; 5];
+
+    /// Reads the result of the previous permutation and prepares the next one.
+    ///
+    /// Returns the number of rounds of the next permutation (8 or 12), or `None` once done.
+    fn step(&mut self, state: &mut [u64; 5]) -> Option<usize>;
+}
+
+/// Runs every job to completion, [`LANES`] at a time.
+///
+/// A lane is refilled with the next job as soon as its current one is done, so jobs of different
+/// lengths do not wait for each other.
+pub(crate) fn run<J: LaneJob>(jobs: &mut [J]) {
+    let mut states = [[0; 5]; LANES];
+    let mut rounds = [8; LANES];
+    let mut lanes: [Option<usize>; LANES] = [None; LANES];
+    let mut next = 0;
+
+    for lane in 0..LANES {
+        lanes[lane] = refill(jobs, &mut next, &mut states[lane], &mut rounds[lane]);
+    }
+
+    while lanes.iter().any(Option::is_some) {
+        // Idle lanes are permuted too, their result is simply ignored.
+        permute(&mut states, rounds);
+
+        for lane in 0..LANES {
+            let Some(j) = lanes[lane] else {
+                continue;
+            };
+
+            match jobs[j].step(&mut states[lane]) {
+                Some(r) => rounds[lane] = r,
+                None => {
+                    lanes[lane] = refill(jobs, &mut next, &mut states[lane], &mut rounds[lane]);
+                }
+            }
+        }
+    }
+
+    states.iter_mut().for_each(|s| s.zeroize());
+}
+
+/// Starts the first job from `jobs[*next..]` which is not done right away in a lane, and returns
+/// its index.
+fn refill<J: LaneJob>(
+    jobs: &mut [J],
+    next: &mut usize,
+    state: &mut [u64; 5],
+    rounds: &mut usize,
+) -> Option<usize> {
+    while *next < jobs.len() {
+        let j = *next;
+        *next += 1;
+
+        *state = jobs[j].start();
+        if let Some(r) = jobs[j].step(state) {
+            *rounds = r;
+            return Some(j);
+        }
+    }
+
+    None
+}
+
+/// Applies `rounds[i]` rounds to `states[i]`, for each lane.
+///
+/// # Panics
+///
+/// In debug builds, if a number of rounds is neither 8 nor 12.
+pub(crate) fn permute(states: &mut [[u64; 5]; LANES], rounds: [usize; LANES]) {
+    debug_assert!(rounds.iter().all(|&r| r == 8 || r == 12));
+
+    #[cfg(target_arch = "x86_64")]
+    if !super::INTERLEAVED {
+        #[cfg(feature = "std")]
+        if std::is_x86_feature_detected!("avx2") {
+            // Safety: AVX2 is available on this CPU.
+            unsafe { avx2::permute(states, rounds) };
+            return;
+        }
+
+        // Safety: SSE2 is part of x86_64.
+        unsafe { sse2::permute(states, rounds) };
+    }
+
+    for (state, &n) in states.iter_mut().zip(&rounds) {
+        super::do_n_rounds_encoded(state, n);
+    }
+}
+
+/// Generates the permutation of as many states as a `$t` register holds lanes.
+///
+/// Word `i` of every lane is held in the register `x[i]`. Lanes whose `mask` is all ones get 12
+/// rounds, the other ones get 8: as both end with the same 8 rounds, the first 4 rounds are
+/// computed for every lane but only kept for the masked ones.
+#[cfg(target_arch = "x86_64")]
+macro_rules! impl_permute_regs {
+    ($feature:literal, $t:ty, $xor:ident, $and:ident, $andnot:ident, $or:ident, $set1:ident,
+     $srli:ident, $slli:ident) => {
+        /// Rotates every 64 bits lane of `x` by `R` bits to the right, `L` being `64 - R`.
+        #[inline]
+        #[target_feature(enable = $feature)]
+        fn rotate_right<const R: i32, const L: i32>(x: $t) -> $t {
+            $or($srli::<R>(x), $slli::<L>(x))
+        }
+
+        /// This is the round function of `Ascon`, see [`super::super::round`].
+        #[inline]
+        #[target_feature(enable = $feature)]
+        fn round(x: &mut [$t; 5], round_constant: u64) {
+            // Constant Addition Layer
+            x[2] = $xor(x[2], $set1(round_constant as i64));
+
+            // Substitution Layer (S-Box)
+            x[0] = $xor(x[0], x[4]);
+            x[2] = $xor(x[2], x[1]);
+            x[4] = $xor(x[4], x[3]);
+
+            let mut t0 = $xor(x[0], $andnot(x[1], x[2]));
+            let mut t1 = $xor(x[1], $andnot(x[2], x[3]));
+            let mut t2 = $xor(x[2], $andnot(x[3], x[4]));
+            let mut t3 = $xor(x[3], $andnot(x[4], x[0]));
+            let t4 = $xor(x[4], $andnot(x[0], x[1]));
+
+            t1 = $xor(t1, t0);
+            t0 = $xor(t0, t4);
+            t3 = $xor(t3, t2);
+            t2 = $xor(t2, $set1(-1));
+
+            // Linear Diffusion Layer
+            x[0] = $xor(
+                t0,
+                $xor(rotate_right::<19, 45>(t0), rotate_right::<28, 36>(t0)),
+            );
+            x[1] = $xor(
+                t1,
+                $xor(rotate_right::<61, 3>(t1), rotate_right::<39, 25>(t1)),
+            );
+            x[2] = $xor(
+                t2,
+                $xor(rotate_right::<1, 63>(t2), rotate_right::<6, 58>(t2)),
+            );
+            x[3] = $xor(
+                t3,
+                $xor(rotate_right::<10, 54>(t3), rotate_right::<17, 47>(t3)),
+            );
+            x[4] = $xor(
+                t4,
+                $xor(rotate_right::<7, 57>(t4), rotate_right::<41, 23>(t4)),
+            );
+        }
+
+        #[target_feature(enable = $feature)]
+        fn permute_regs(x: &mut [$t; 5], mask: $t) {
+            let mut y = *x;
+            for &c in &super::super::ROUND_CONSTANTS[4..8] {
+                round(&mut y, c);
+            }
+            for (x, y) in x.iter_mut().zip(y) {
+                *x = $or($and(mask, y), $andnot(mask, *x));
+            }
+
+            for &c in &super::super::ROUND_CONSTANTS[8..16] {
+                round(x, c);
+            }
+        }
+    };
+}
+
+#[cfg(all(target_arch = "x86_64", feature = "std"))]
+pub(crate) mod avx2 {
+    use core::arch::x86_64::*;
+
+    use super::LANES;
+
+    impl_permute_regs!(
+        "avx2",
+        __m256i,
+        _mm256_xor_si256,
+        _mm256_and_si256,
+        _mm256_andnot_si256,
+        _mm256_or_si256,
+        _mm256_set1_epi64x,
+        _mm256_srli_epi64,
+        _mm256_slli_epi64
+    );
+
+    /// Same as [`super::permute`], with one AVX2 register per word.
+    #[target_feature(enable = "avx2")]
+    pub(crate) fn permute(states: &mut [[u64; 5]; LANES], rounds: [usize; LANES]) {
+        let lane =
+            |s: [u64; LANES]| _mm256_set_epi64x(s[3] as i64, s[2] as i64, s[1] as i64, s[0] as i64);
+
+        let mut x: [__m256i; 5] = core::array::from_fn(|i| lane(states.map(|s| s[i])));
+        let mask = lane(rounds.map(|r| if r == 12 { u64::MAX } else { 0 }));
+
+        permute_regs(&mut x, mask);
+
+        for (i, x) in x.into_iter().enumerate() {
+            states[0][i] = _mm256_extract_epi64::<0>(x) as u64;
+            states[1][i] = _mm256_extract_epi64::<1>(x) as u64;
+            states[2][i] = _mm256_extract_epi64::<2>(x) as u64;
+            states[3][i] = _mm256_extract_epi64::<3>(x) as u64;
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+pub(crate) mod sse2 {
+    use core::arch::x86_64::*;
+
+    use super::LANES;
+
+    impl_permute_regs!(
+        "sse2",
+        __m128i,
+        _mm_xor_si128,
+        _mm_and_si128,
+        _mm_andnot_si128,
+        _mm_or_si128,
+        _mm_set1_epi64x,
+        _mm_srli_epi64,
+        _mm_slli_epi64
+    );
+
+    /// Same as [`super::permute`], with one SSE2 register per word and pair of lanes.
+    #[target_feature(enable = "sse2")]
+    pub(crate) fn permute(states: &mut [[u64; 5]; LANES], rounds: [usize; LANES]) {
+        let mask = |r: usize| if r == 12 { -1 } else { 0 };
+
+        for (states, rounds) in states.chunks_exact_mut(2).zip(rounds.chunks_exact(2)) {
+            let mut x: [__m128i; 5] =
+                core::array::from_fn(|i| _mm_set_epi64x(states[1][i] as i64, states[0][i] as i64));
+
+            permute_regs(&mut x, _mm_set_epi64x(mask(rounds[1]), mask(rounds[0])));
+
+            for (i, x) in x.into_iter().enumerate() {
+                states[0][i] = _mm_cvtsi128_si64(x) as u64;
+                states[1][i] = _mm_cvtsi128_si64(_mm_unpackhi_epi64(x, x)) as u64;
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    aead128::Aead128Cipher,
+    types::{Key, Nonce, Tag},
+    Error,
+};
+
+/// Length of the nonce prefix chosen by the caller.
+///
+/// The `Ascon-AEAD128` nonce of each segment is built as:
+/// |  prefix  |  counter  | last flag |
+/// | 11 bytes |  4 bytes  |  1 byte   |
+/// with the counter in big-endian, and the flag set to `1` for the last segment only.
+pub const NONCE_PREFIX_LEN: usize = 11;
+
+/// Builds the nonce of the segment number `counter`.
+fn segment_nonce(prefix: &[u8; NONCE_PREFIX_LEN], counter: u32, last: bool) -> Nonce {
+    let mut nonce = [0; 16];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..15].copy_from_slice(&counter.to_be_bytes());
+    nonce[15] = last as u8;
+
+    Nonce::from(nonce)
+}
+
+/// Online authenticated encryption of a stream split in segments (the `STREAM` construction).
+///
+/// Every segment is encrypted on its own with [`Aead128Cipher`], under a nonce derived from the
+/// nonce prefix and the segment position. The last segment is flagged in its nonce so that a
+/// truncated stream can not be mistaken for a complete one. A given `(key, nonce prefix)` pair
+/// must only be used for a single stream.
+pub struct StreamEncryptor {
+    cipher: Aead128Cipher,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    /// Position of the next segment.
+    counter: u32,
+}
+
+impl fmt::Debug for StreamEncryptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamEncryptor")
+            .field("prefix", &self.prefix)
+            .field("counter", &self.counter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StreamEncryptor {
+    pub fn new(key: &Key, nonce_prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            cipher: Aead128Cipher::new(key),
+            prefix: nonce_prefix,
+            counter: 0,
+        }
+    }
+
+    /// Encrypts the next segment of the stream, which must not be the last one.
+    ///
+    /// Returns `(ciphertext, tag)`, as [`Aead128Cipher::encrypt`] does, or
+    /// [`Error::DataLimitExceeded`] if the stream already holds `u32::MAX` segments.
+    pub fn encrypt_next(&mut self, ad: &[u8], segment: &[u8]) -> Result<(Vec<u8>, Tag), Error> {
+        let nonce = segment_nonce(&self.prefix, self.counter, false);
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or(Error::DataLimitExceeded)?;
+
+        Ok(self.cipher.encrypt(&nonce, ad, segment))
+    }
+
+    /// Encrypts the last segment of the stream.
+    pub fn encrypt_last(self, ad: &[u8], segment: &[u8]) -> (Vec<u8>, Tag) {
+        let nonce = segment_nonce(&self.prefix, self.counter, true);
+
+        self.cipher.encrypt(&nonce, ad, segment)
+    }
+}
+
+/// Decryption side of [`StreamEncryptor`].
+///
+/// Each segment is only returned once its own tag is verified, so no unauthenticated plaintext is
+/// ever released. Segments must be given in order, and the stream is complete only once
+/// [`StreamDecryptor::decrypt_last`] succeeded.
+pub struct StreamDecryptor {
+    cipher: Aead128Cipher,
+    prefix: [u8; NONCE_PREFIX_LEN],
+    /// Position of the next segment.
+    counter: u32,
+}
+
+impl fmt::Debug for StreamDecryptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamDecryptor")
+            .field("prefix", &self.prefix)
+            .field("counter", &self.counter)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StreamDecryptor {
+    pub fn new(key: &Key, nonce_prefix: [u8; NONCE_PREFIX_LEN]) -> Self {
+        Self {
+            cipher: Aead128Cipher::new(key),
+            prefix: nonce_prefix,
+            counter: 0,
+        }
+    }
+
+    /// Decrypts the next segment of the stream, which must not be the last one.
+    ///
+    /// Returns the plaintext on success, and [`Error::AuthenticationFailed`] if the segment was
+    /// altered, reordered, or actually is the last one. A failed segment does not advance the
+    /// stream.
+    pub fn decrypt_next(&mut self, ad: &[u8], cipher: &[u8], tag: &Tag) -> Result<Vec<u8>, Error> {
+        // The encryptor can not produce more segments than this, so this one can not be valid.
+        let next = self
+            .counter
+            .checked_add(1)
+            .ok_or(Error::DataLimitExceeded)?;
+
+        let nonce = segment_nonce(&self.prefix, self.counter, false);
+        let plain = self.cipher.decrypt(&nonce, ad, cipher, tag)?;
+        self.counter = next;
+
+        Ok(plain)
+    }
+
+    /// Decrypts the last segment of the stream.
+    ///
+    /// Returns the plaintext on success, and [`Error::AuthenticationFailed`] if the segment was
+    /// altered, reordered, or is not the last one (i.e. the stream was truncated).
+    pub fn decrypt_last(self, ad: &[u8], cipher: &[u8], tag: &Tag) -> Result<Vec<u8>, Error> {
+        let nonce = segment_nonce(&self.prefix, self.counter, true);
+
+        self.cipher.decrypt(&nonce, ad, cipher, tag)
+    }
+}
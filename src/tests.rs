@@ -1,6 +1,16 @@
-use crate::aead128::AEAD128;
-use crate::hash256::Hash256;
-use crate::utils::pad_u64;
+use crate::aead128::{Aead128Cipher, Aead128Decryptor, Aead128Encryptor, AEAD128};
+use crate::hash256::{self, Cxof128, Hash256, Xof128, MAX_CUSTOMIZATION_LEN};
+use crate::kat;
+use crate::legacy_aead::{Ascon128, Ascon128a, Ascon80pq};
+use crate::legacy_hash::{AsconHash, AsconHasha, AsconXof, AsconXofa};
+use crate::mac::{AsconMac, AsconPrf, AsconPrfShort};
+use crate::permutation::{Permutation, State};
+use crate::reference;
+use crate::round::{self, interleaved, lanes};
+use crate::stream::{StreamDecryptor, StreamEncryptor};
+use crate::types::{Key, Nonce, Tag};
+use crate::utils::{self, ct_eq, pad_u64, Zeroize};
+use crate::Error;
 
 #[test]
 fn test_pad_u64() {
@@ -15,16 +25,18 @@ fn test_pad_u64() {
 }
 
 fn run_test_aead(key: [u8; 16], nonce: [u8; 16], ad: &[u8], plain: &[u8]) {
-    let (cipher, mut tag) = AEAD128::encrypt(key, nonce, &ad, &plain);
+    let (key, nonce) = (Key::from(key), Nonce::from(nonce));
+    let (cipher, tag) = AEAD128::encrypt(&key, &nonce, ad, plain);
 
-    let decipher = AEAD128::decrypt(key, nonce, &ad, &cipher, tag);
+    let decipher = AEAD128::decrypt(&key, &nonce, ad, &cipher, &tag);
 
-    assert_eq!(decipher, Some(plain.to_vec()));
+    assert_eq!(decipher, Ok(plain.to_vec()));
 
     // Try deciphering with after altering the `tag`. This should fail.
-    tag[0] += 1;
-    let decipher = AEAD128::decrypt(key, nonce, &ad, &cipher, tag);
-    assert!(decipher.is_none());
+    let mut tag = *tag.as_bytes();
+    tag[0] = tag[0].wrapping_add(1);
+    let decipher = AEAD128::decrypt(&key, &nonce, ad, &cipher, &Tag::from(tag));
+    assert_eq!(decipher, Err(Error::AuthenticationFailed));
 }
 
 fn run_test_hash(msg: &[u8], expected: &[u8; 32]) {
@@ -250,3 +262,1532 @@ fn test_hashes() {
         run_test_hash(&msg, &expected);
     }
 }
+
+/// Decodes the hex strings of the test vectors.
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_xof128() {
+    let expecteds = [
+        (
+            0,
+            "473d5e6164f58b39dfd84aacdb8ae42ec2d91fed33388ee0d960d9b3993295c6\
+             ad77855a5d3b13fe6ad9e6098988373af7d0956d05a8f1665d2c67d1a3ad10ff",
+        ),
+        (
+            1,
+            "51430e0438ecdf642b393630d977625f5f337656ba58ab1e960784ac32a16e0d\
+             446405551f5469384f8ea283cf12e64fa72c426bfebaea3aa1529e2c4ab23a2f",
+        ),
+        (
+            7,
+            "7ae562db37212a9acd2673ecfd5b4f1c5cb2e6f64ebf00aa7f6ef8dc82c448d5\
+             fe11cd91f4368c37690d79e5de0ca8ad419e1918ce8dab2d42363e9476638a7b",
+        ),
+        (
+            9,
+            "db3013bfbbd132dc1d3152fd955ed48f7cbb675e9ad2a2fecf92b74c957592e0\
+             c89959e81c16fd07ead9eeb8e40359c497aa20258b43d87ec69ad0bb0993fd38",
+        ),
+    ];
+
+    for (i, expected) in expecteds {
+        let msg: Vec<u8> = (0..i).map(|x| x as u8).collect();
+        let expected = from_hex(expected);
+
+        // Any output length is a prefix of the longer output.
+        for len in [0, 1, 7, 13, 32, 45, 64] {
+            let mut out = vec![0; len];
+            Xof128::xof(&msg).squeeze(&mut out);
+            assert_eq!(out, expected[..len]);
+        }
+    }
+}
+
+#[test]
+fn test_xof128_squeeze_in_pieces() {
+    let msg: Vec<u8> = (0..33).collect();
+    let mut expected = [0; 100];
+    Xof128::xof(&msg).squeeze(&mut expected);
+
+    for piece in 1..20 {
+        let mut reader = Xof128::xof(&msg);
+        let mut out = Vec::new();
+        while out.len() < expected.len() {
+            let mut buf = vec![0; piece.min(expected.len() - out.len())];
+            reader.squeeze(&mut buf);
+            out.extend_from_slice(&buf);
+        }
+        assert_eq!(out, expected);
+    }
+}
+
+#[test]
+fn test_cxof128() {
+    // (customization length, message length, expected output)
+    let expecteds = [
+        (
+            0,
+            0,
+            "4f50159ef70bb3dad8807e034eaebd44c4fa2cbbc8cf1f05511ab66cdcc52990",
+        ),
+        (
+            1,
+            0,
+            "0c93a483e7d574d49fe52cce03ee646117977d57a8aa57704ab4daf44b501430",
+        ),
+        (
+            0,
+            1,
+            "7f0c0ddd4bc9603deed19510cdb954d65cf254f59234bfbf5a730d03d2712daa",
+        ),
+        (
+            8,
+            9,
+            "dbd6e0f7a36470698b031f8b798c51096f00eaac70b89d0d0838fb8c6086aebb",
+        ),
+        (
+            13,
+            33,
+            "f1e99c97768dae7795978e1f04c63a34eeee711e01e465337a0c9b6e3e74d5ed",
+        ),
+        (
+            256,
+            7,
+            "58cee9280047394dbb90c4bb752705532ad56abba56593c94cd0e325e8044d41",
+        ),
+    ];
+
+    for (z, i, expected) in expecteds {
+        let customization: Vec<u8> = (16..16 + z).map(|x| x as u8).collect();
+        let msg: Vec<u8> = (0..i).map(|x| x as u8).collect();
+        let expected = from_hex(expected);
+
+        for len in [5, 32] {
+            let mut out = vec![0; len];
+            Cxof128::xof(&customization, &msg)
+                .unwrap()
+                .squeeze(&mut out);
+            assert_eq!(out, expected[..len]);
+        }
+    }
+}
+
+#[test]
+fn test_cxof128_customization_too_long() {
+    let customization = [0; MAX_CUSTOMIZATION_LEN + 1];
+    let err = Cxof128::xof(&customization, &[]).unwrap_err();
+    assert_eq!(err, Error::DataLimitExceeded);
+}
+
+#[test]
+fn test_hash256_incremental() {
+    let msg: Vec<u8> = (0..200).map(|x| x as u8).collect();
+
+    for len in [0, 1, 7, 8, 9, 64, 200] {
+        let expected = Hash256::hash(&msg[..len]);
+
+        for chunk in 1..=17 {
+            let mut hash256 = Hash256::new();
+            for c in msg[..len].chunks(chunk) {
+                hash256.update(c);
+            }
+            assert_eq!(hash256.finalize(), expected);
+        }
+
+        // Empty updates should not change anything.
+        let mut hash256 = Hash256::new();
+        hash256.update(&[]);
+        hash256.update(&msg[..len / 2]);
+        hash256.update(&[]);
+        hash256.update(&msg[len / 2..len]);
+        assert_eq!(hash256.finalize(), expected);
+    }
+}
+
+/// Encrypts then decrypts with the incremental API, feeding it `chunk` bytes at a time, and checks
+/// that it gives the same bytes as the one-shot functions.
+fn run_test_aead_incremental(ad: &[u8], plain: &[u8], chunk: usize) {
+    let key = Key::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let nonce = Nonce::from([15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+    let (expected_cipher, expected_tag) = AEAD128::encrypt(&key, &nonce, ad, plain);
+
+    let mut encryptor = Aead128Encryptor::new(&key, &nonce);
+    for c in ad.chunks(chunk) {
+        encryptor.update_ad(c).unwrap();
+    }
+    let mut cipher = Vec::new();
+    for c in plain.chunks(chunk) {
+        cipher.extend(encryptor.update(c));
+    }
+    let (last, tag) = encryptor.finalize();
+    cipher.extend(last);
+
+    assert_eq!(cipher, expected_cipher);
+    assert_eq!(tag, expected_tag);
+
+    let mut decryptor = Aead128Decryptor::new(&key, &nonce);
+    for c in ad.chunks(chunk) {
+        decryptor.update_ad(c).unwrap();
+    }
+    let mut decipher = Vec::new();
+    for c in cipher.chunks(chunk) {
+        decipher.extend(decryptor.update(c));
+    }
+    decipher.extend(decryptor.finalize(&tag).unwrap());
+
+    assert_eq!(decipher, plain);
+}
+
+#[test]
+fn test_aead_incremental() {
+    let data: Vec<u8> = (0..70).map(|x| x as u8).collect();
+
+    for ad_len in [0, 1, 8, 15, 16, 17, 33] {
+        for plain_len in [0, 1, 8, 15, 16, 17, 32, 70] {
+            for chunk in 1..=20 {
+                run_test_aead_incremental(&data[..ad_len], &data[..plain_len], chunk);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_aead_incremental_invalid_tag() {
+    let key = Key::from([0; 16]);
+    let nonce = Nonce::from([1; 16]);
+    let (cipher, tag) = AEAD128::encrypt(&key, &nonce, b"ad", b"some plaintext");
+    let mut tag = *tag.as_bytes();
+    tag[15] ^= 1;
+
+    let mut decryptor = Aead128Decryptor::new(&key, &nonce);
+    decryptor.update_ad(b"ad").unwrap();
+    decryptor.update(&cipher);
+    assert_eq!(
+        decryptor.finalize(&Tag::from(tag)),
+        Err(Error::AuthenticationFailed)
+    );
+}
+
+#[test]
+fn test_aead_incremental_late_ad() {
+    let mut encryptor = Aead128Encryptor::new(&Key::from([0; 16]), &Nonce::from([0; 16]));
+    encryptor.update_ad(b"ad").unwrap();
+    encryptor.update(b"plain");
+    assert_eq!(
+        encryptor.update_ad(b"ad"),
+        Err(Error::AssociatedDataAfterMessage)
+    );
+
+    // Even empty, as the message has started.
+    let mut decryptor = Aead128Decryptor::new(&Key::from([0; 16]), &Nonce::from([0; 16]));
+    decryptor.update(b"cipher");
+    assert_eq!(
+        decryptor.update_ad(b""),
+        Err(Error::AssociatedDataAfterMessage)
+    );
+}
+
+/// Encrypts `segments` as a stream, the last one being flagged as such.
+fn encrypt_stream(segments: &[&[u8]]) -> Vec<(Vec<u8>, Tag)> {
+    let mut encryptor = StreamEncryptor::new(&Key::from([7; 16]), [3; 11]);
+    let (last, segments) = segments.split_last().unwrap();
+
+    let mut out: Vec<_> = segments
+        .iter()
+        .map(|s| encryptor.encrypt_next(b"header", s).unwrap())
+        .collect();
+    out.push(encryptor.encrypt_last(b"header", last));
+
+    out
+}
+
+#[test]
+fn test_stream() {
+    let segments: [&[u8]; 4] = [b"first segment", b"", b"third segment", b"last one"];
+    let encrypted = encrypt_stream(&segments);
+
+    let mut decryptor = StreamDecryptor::new(&Key::from([7; 16]), [3; 11]);
+    let (last, others) = encrypted.split_last().unwrap();
+    for ((cipher, tag), expected) in others.iter().zip(segments) {
+        assert_eq!(
+            decryptor.decrypt_next(b"header", cipher, tag).unwrap(),
+            expected
+        );
+    }
+    assert_eq!(
+        decryptor.decrypt_last(b"header", &last.0, &last.1).unwrap(),
+        segments[3]
+    );
+
+    // Identical segments at different positions are encrypted differently.
+    let encrypted = encrypt_stream(&[b"same", b"same"]);
+    assert_ne!(encrypted[0], encrypted[1]);
+}
+
+#[test]
+fn test_stream_reordered() {
+    let encrypted = encrypt_stream(&[b"first", b"second", b"last"]);
+
+    let mut decryptor = StreamDecryptor::new(&Key::from([7; 16]), [3; 11]);
+    assert!(decryptor
+        .decrypt_next(b"header", &encrypted[1].0, &encrypted[1].1)
+        .is_err());
+
+    // A failed segment does not advance the stream.
+    assert!(decryptor
+        .decrypt_next(b"header", &encrypted[0].0, &encrypted[0].1)
+        .is_ok());
+}
+
+#[test]
+fn test_stream_truncated() {
+    let encrypted = encrypt_stream(&[b"first", b"second", b"last"]);
+
+    // Dropping the last segment: the one before is not flagged as last.
+    let mut decryptor = StreamDecryptor::new(&Key::from([7; 16]), [3; 11]);
+    assert!(decryptor
+        .decrypt_next(b"header", &encrypted[0].0, &encrypted[0].1)
+        .is_ok());
+    assert!(decryptor
+        .decrypt_last(b"header", &encrypted[1].0, &encrypted[1].1)
+        .is_err());
+
+    // The last segment can not be taken for a regular one either.
+    let mut decryptor = StreamDecryptor::new(&Key::from([7; 16]), [3; 11]);
+    assert!(decryptor
+        .decrypt_next(b"header", &encrypted[0].0, &encrypted[0].1)
+        .is_ok());
+    assert!(decryptor
+        .decrypt_next(b"header", &encrypted[1].0, &encrypted[1].1)
+        .is_ok());
+    assert!(decryptor
+        .decrypt_next(b"header", &encrypted[2].0, &encrypted[2].1)
+        .is_err());
+}
+
+#[test]
+fn test_aead_in_place() {
+    let key = Key::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let nonce = Nonce::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let ad = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+    let data: Vec<u8> = (0..40).collect();
+
+    for len in 0..data.len() {
+        let plain = &data[..len];
+        let (expected_cipher, expected_tag) = AEAD128::encrypt(&key, &nonce, &ad, plain);
+
+        let mut buffer = plain.to_vec();
+        let tag = AEAD128::encrypt_in_place_detached(&key, &nonce, &ad, &mut buffer);
+        assert_eq!(buffer, expected_cipher);
+        assert_eq!(tag, expected_tag);
+
+        AEAD128::decrypt_in_place_detached(&key, &nonce, &ad, &mut buffer, &tag).unwrap();
+        assert_eq!(buffer, plain);
+
+        let mut out = vec![0; len];
+        let tag = AEAD128::encrypt_into(&key, &nonce, &ad, plain, &mut out).unwrap();
+        assert_eq!(out, expected_cipher);
+        assert_eq!(tag, expected_tag);
+
+        let mut decipher = vec![0; len];
+        AEAD128::decrypt_into(&key, &nonce, &ad, &out, &mut decipher, &tag).unwrap();
+        assert_eq!(decipher, plain);
+    }
+}
+
+#[test]
+fn test_aead_in_place_invalid_tag() {
+    let key = Key::from([0; 16]);
+    let nonce = Nonce::from([1; 16]);
+    let mut buffer = *b"attack at dawn!!!";
+    let tag = AEAD128::encrypt_in_place_detached(&key, &nonce, &[], &mut buffer);
+    let mut tag = *tag.as_bytes();
+    tag[0] ^= 1;
+    let tag = Tag::from(tag);
+
+    assert_eq!(
+        AEAD128::decrypt_in_place_detached(&key, &nonce, &[], &mut buffer, &tag),
+        Err(Error::AuthenticationFailed)
+    );
+    // Nothing is left in the buffer.
+    assert_eq!(buffer, [0; 17]);
+}
+
+#[test]
+fn test_aead_into_invalid_length() {
+    let key = Key::from([0; 16]);
+    let nonce = Nonce::from([1; 16]);
+    let mut out = [0; 4];
+
+    assert_eq!(
+        AEAD128::encrypt_into(&key, &nonce, &[], b"plain", &mut out),
+        Err(Error::InvalidLength {
+            expected: 5,
+            actual: 4
+        })
+    );
+    assert_eq!(
+        AEAD128::decrypt_into(&key, &nonce, &[], b"c", &mut out, &Tag::from([0; 16])),
+        Err(Error::InvalidLength {
+            expected: 1,
+            actual: 4
+        })
+    );
+}
+
+#[test]
+fn test_aead_cipher() {
+    let key = Key::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let cipher = Aead128Cipher::new(&key);
+    let data: Vec<u8> = (0..40).collect();
+
+    for len in 0..data.len() {
+        let nonce = Nonce::from([len as u8; 16]);
+        let (expected_cipher, expected_tag) =
+            AEAD128::encrypt(&key, &nonce, &data[..len / 2], &data[..len]);
+
+        let (encrypted, tag) = cipher.encrypt(&nonce, &data[..len / 2], &data[..len]);
+        assert_eq!(encrypted, expected_cipher);
+        assert_eq!(tag, expected_tag);
+        assert_eq!(
+            cipher.decrypt(&nonce, &data[..len / 2], &encrypted, &tag),
+            Ok(data[..len].to_vec())
+        );
+        assert_eq!(
+            cipher.decrypt(&nonce, &data[..len / 2 + 1], &encrypted, &tag),
+            Err(Error::AuthenticationFailed)
+        );
+
+        let mut buffer = data[..len].to_vec();
+        let tag = cipher.encrypt_in_place_detached(&nonce, &data[..len / 2], &mut buffer);
+        assert_eq!(buffer, expected_cipher);
+        assert_eq!(tag, expected_tag);
+        cipher
+            .decrypt_in_place_detached(&nonce, &data[..len / 2], &mut buffer, &tag)
+            .unwrap();
+        assert_eq!(buffer, &data[..len]);
+    }
+}
+
+#[test]
+fn test_aead_cipher_shared() {
+    fn assert_shareable<T: Send + Sync + Clone>() {}
+    assert_shareable::<Aead128Cipher>();
+
+    let key = Key::from([7; 16]);
+    let cipher = Aead128Cipher::new(&key);
+
+    // Every thread encrypts its own packet with the same instance.
+    std::thread::scope(|s| {
+        for i in 0..4u8 {
+            let cipher = &cipher;
+            let key = &key;
+            s.spawn(move || {
+                let nonce = Nonce::from([i; 16]);
+                let packet = [i; 33];
+                let (encrypted, tag) = cipher.encrypt(&nonce, b"header", &packet);
+                assert_eq!(
+                    (encrypted.clone(), tag),
+                    AEAD128::encrypt(key, &nonce, b"header", &packet)
+                );
+                assert_eq!(
+                    cipher.clone().decrypt(&nonce, b"header", &encrypted, &tag),
+                    Ok(packet.to_vec())
+                );
+            });
+        }
+    });
+}
+
+#[test]
+fn test_ct_eq() {
+    assert!(ct_eq(&[], &[]));
+    assert!(ct_eq(&[1, 2, 3], &[1, 2, 3]));
+    assert!(!ct_eq(&[1, 2, 3], &[1, 2, 4]));
+    assert!(!ct_eq(&[0, 2, 3], &[1, 2, 3]));
+    assert!(!ct_eq(&[1, 2, 3], &[1, 2]));
+}
+
+#[test]
+fn test_ct_eq_reads_everything() {
+    let a = [0x5a; 64];
+
+    // Wherever the first difference is, if any, every pair of bytes is read.
+    for mismatch in (0..a.len()).map(Some).chain([None]) {
+        let mut b = a;
+        if let Some(i) = mismatch {
+            b[i] ^= 1;
+        }
+
+        let mut read = 0;
+        let diff = utils::diff(a.iter().zip(&b).inspect(|_| read += 1));
+
+        assert_eq!(read, a.len(), "mismatch at {:?}", mismatch);
+        assert_eq!(diff == 0, mismatch.is_none(), "mismatch at {:?}", mismatch);
+        assert_eq!(
+            ct_eq(&a, &b),
+            mismatch.is_none(),
+            "mismatch at {:?}",
+            mismatch
+        );
+    }
+}
+
+#[test]
+fn test_mac() {
+    let key = Key::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let expecteds = [
+        (0, "eb1af688825d66bf2d53e135f9323315"),
+        (1, "81f3c3537c5595aaa0d5780b9f88a043"),
+        (16, "a7915e83ee1aa71422cfd90868e22dc2"),
+        (17, "14b54fe404e4110951cb0be8ab07518f"),
+        // Around the 32 bytes block boundary, and over several blocks.
+        (31, "b6424fd4c356ef1d510682b108693890"),
+        (32, "892523d61028799c507d1644126f03ef"),
+        (33, "fbbfa47c9364499b9526f4cd0d94f9e4"),
+        (39, "cacb738ed0f685b6242b2ed054882845"),
+        (40, "476eaec5ac6084976501446eaf6180b5"),
+        (41, "b764e0e390c1a0dcd4dbe769fee90298"),
+        (64, "edc563c5a0bb6761073f8a6fb6238234"),
+        (100, "13e936368eb9de4a6888e2472a82dbc1"),
+    ];
+
+    for (i, expected) in expecteds {
+        let msg: Vec<u8> = (0..i).map(|x| x as u8).collect();
+        let tag = AsconMac::mac(&key, &msg);
+        assert_eq!(tag.as_bytes().to_vec(), from_hex(expected));
+
+        assert_eq!(AsconMac::verify(&key, &msg, &tag), Ok(()));
+        let mut bytes = *tag.as_bytes();
+        bytes[15] ^= 0x80;
+        assert_eq!(
+            AsconMac::verify(&key, &msg, &Tag::from(bytes)),
+            Err(Error::AuthenticationFailed)
+        );
+    }
+}
+
+#[test]
+fn test_prf() {
+    let key = Key::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let expecteds = [
+        (
+            0,
+            "2a766fe9a4894073bc811b19d54ac33da3781e8fa3f548bf5cd8d8555559e6b7aae65348e1f8963d",
+        ),
+        (1, "62dcf5fd8253089b765e2cf1a0d1a4fa"),
+        (16, "87287b11bfbcc92d43e3667f7ac30c90"),
+        (32, "5674455f29416f5081d05ee3c31e286b"),
+        (
+            41,
+            "f6c77fec0575f444cf50447c32e0191c1f8925bf6206771af898f42d192dfdc0ccb2a78b0902cb3f",
+        ),
+    ];
+
+    for (i, expected) in expecteds {
+        let msg: Vec<u8> = (0..i).map(|x| x as u8).collect();
+        let expected = from_hex(expected);
+
+        // Shorter outputs are prefixes of the longer ones.
+        for len in [0, 5, 16, expected.len()] {
+            let mut out = vec![0; len];
+            AsconPrf::prf(&key, &msg, &mut out);
+            assert_eq!(out, expected[..len]);
+        }
+    }
+}
+
+#[test]
+fn test_prf_short() {
+    let key = Key::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let expecteds = [
+        (0, 16, "5006eb1808193809f981151b19e59299"),
+        (1, 16, "bde4e1a8fb90cd5a2f2dba6184b65395"),
+        (16, 16, "bd03ea334bebefc4d7ddaef4b1df1485"),
+        // The output length is part of the IV.
+        (5, 8, "845c1d8487095e9f"),
+    ];
+
+    for (i, len, expected) in expecteds {
+        let msg: Vec<u8> = (0..i).map(|x| x as u8).collect();
+        let mut out = vec![0; len];
+        AsconPrfShort::prf(&key, &msg, &mut out).unwrap();
+        assert_eq!(out, from_hex(expected));
+    }
+
+    let msg = [0, 1, 2];
+    let mut tag = [0; 16];
+    AsconPrfShort::prf(&key, &msg, &mut tag).unwrap();
+    assert_eq!(AsconPrfShort::verify(&key, &msg, &Tag::from(tag)), Ok(()));
+    tag[0] ^= 1;
+    assert_eq!(
+        AsconPrfShort::verify(&key, &msg, &Tag::from(tag)),
+        Err(Error::AuthenticationFailed)
+    );
+}
+
+#[test]
+fn test_prf_short_too_long() {
+    assert_eq!(
+        AsconPrfShort::prf(&Key::from([0; 16]), &[0; 17], &mut [0; 16]),
+        Err(Error::DataLimitExceeded)
+    );
+    assert_eq!(
+        AsconPrfShort::prf(&Key::from([0; 16]), &[0; 16], &mut [0; 17]),
+        Err(Error::InvalidTagLength { len: 17 })
+    );
+}
+
+type EncryptFn<K> = fn(&K, &Nonce, &[u8], &[u8]) -> (Vec<u8>, Tag);
+type DecryptFn<K> = fn(&K, &Nonce, &[u8], &[u8], &Tag) -> Result<Vec<u8>, Error>;
+
+/// Checks a legacy AEAD against `(ad length, plaintext length, ciphertext || tag)` vectors, with
+/// the key, nonce, AD and plaintext being `00 01 02 ...` as in the v1.2 KAT files.
+fn run_test_legacy_aead<K: From<[u8; N]>, const N: usize>(
+    encrypt: EncryptFn<K>,
+    decrypt: DecryptFn<K>,
+    expecteds: &[(usize, usize, &str)],
+) {
+    let key = K::from(core::array::from_fn(|i| i as u8));
+    let nonce = Nonce::from(core::array::from_fn(|i| i as u8));
+
+    for &(ad_len, plain_len, expected) in expecteds {
+        let ad: Vec<u8> = (0..ad_len).map(|x| x as u8).collect();
+        let plain: Vec<u8> = (0..plain_len).map(|x| x as u8).collect();
+
+        let (cipher, tag) = encrypt(&key, &nonce, &ad, &plain);
+        assert_eq!([&cipher[..], tag.as_bytes()].concat(), from_hex(expected));
+
+        assert_eq!(decrypt(&key, &nonce, &ad, &cipher, &tag), Ok(plain));
+        let mut bytes = *tag.as_bytes();
+        bytes[0] ^= 1;
+        assert_eq!(
+            decrypt(&key, &nonce, &ad, &cipher, &Tag::from(bytes)),
+            Err(Error::AuthenticationFailed)
+        );
+    }
+}
+
+#[test]
+fn test_ascon128() {
+    run_test_legacy_aead(
+        Ascon128::encrypt,
+        Ascon128::decrypt,
+        &[
+            (0, 0, "e355159f292911f794cb1432a0103a8a"),
+            (1, 0, "944df887cd4901614c5dedbc42fc0da0"),
+            (0, 1, "bc18c3f4e39eca7222490d967c79bffc92"),
+            (7, 8, "2e5bbade9599ac9f863b01d95b8945703b3b4a99b5e847a1"),
+            (8, 9, "69ffee6f5505a4897ecf5bdd353828b5c3b397863ce8bfd719"),
+            (
+                16,
+                15,
+                "1ee34125fdba17443d01da8a0eefb0cdbac21a17f7627a02b8520502d0a308",
+            ),
+            (
+                17,
+                32,
+                "8684539a9fcff9f68a7a496010f129b5c9a3860bff417050d0281d0ba8f4b8aa\
+                 a418c1534ca1a6c18413253e0325e310",
+            ),
+            (
+                32,
+                33,
+                "b96c78651b6246b0c3b1a5d373b0d5168dca4a96734cf0ddf5f92f8d15e30270\
+                 e53a6747cc9cb8a5ef9bae8d793313a822",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_ascon128a() {
+    run_test_legacy_aead(
+        Ascon128a::encrypt,
+        Ascon128a::decrypt,
+        &[
+            (0, 0, "7a834e6f09210957067b10fd831f0078"),
+            (1, 0, "af3031b07b129ec84153373ddcaba528"),
+            (0, 1, "6e652b55bfdc8cad2ec43815b1666b1a3a"),
+            (7, 8, "aff7dbf309372971bb0268f8bd5155d5d7d6d58590bae1c4"),
+            (8, 9, "34d3b7edb89b1d5067bdac136a8929ae1500559312833b547a"),
+            (
+                16,
+                15,
+                "52499ac9c84323a4ae24eaeccf45c14bb7700c338c95a089f524c515460cc7",
+            ),
+            (
+                17,
+                32,
+                "bc26a071c86e16ad251fd2ad8d3139f43bedcfd8cbacc5c8e2f5bf5c995ef2b0\
+                 13e624b646bf32552615b8a6cb0da908",
+            ),
+            (
+                32,
+                33,
+                "a55236ac020dbda74ce6ccd10c68c4d8514450a382bc87c68946d86a921dd88e\
+                 0a7bb07111857177e06e06d926a6047b7c",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_ascon80pq() {
+    run_test_legacy_aead(
+        Ascon80pq::encrypt,
+        Ascon80pq::decrypt,
+        &[
+            (0, 0, "abb688efa0b9d56b33277a2c97d2146b"),
+            (1, 0, "a259d760e87b0ca73002c3a01e69b567"),
+            (0, 1, "28aa80fff4ca3af32f60ebcaf63a4ccab7"),
+            (7, 8, "4f599ee2f0f9cd87e48e77d80ea260816d4c7836a0e35c8c"),
+            (8, 9, "e16c12dd1db74fa7738eb40abed9f4161ffd1e3224faff94e3"),
+            (
+                16,
+                15,
+                "1db9005057cfc7dcc273a6722b8be18cbd29a97f6ff8648bb191e847566dd9",
+            ),
+            (
+                17,
+                32,
+                "393e98c899061cfcf58c8fdadde7c9e4f58ed2e39c4b7f2053c05963bd3e119a\
+                 736b8c4d9c4b1b0b48d0fb3a34127362",
+            ),
+            (
+                32,
+                33,
+                "cc4e07e5fb13426effd17b0f51a6a830bf484c9651d77679971e8eb4a8edb5a0\
+                 ef300ffe99a790e666f45ae8861c6dd652",
+            ),
+        ],
+    );
+}
+
+/// Checks a legacy hash function against `(message length, digest)` vectors, with the message
+/// being `00 01 02 ...` as in the v1.2 KAT files.
+fn run_test_legacy_hash(hash: fn(&[u8], &mut [u8]), expecteds: &[(usize, &str)]) {
+    for &(i, expected) in expecteds {
+        let msg: Vec<u8> = (0..i).map(|x| x as u8).collect();
+        let mut out = [0; 32];
+        hash(&msg, &mut out);
+        assert_eq!(out.to_vec(), from_hex(expected));
+    }
+}
+
+#[test]
+fn test_ascon_hash() {
+    run_test_legacy_hash(
+        |msg, out| out.copy_from_slice(&AsconHash::hash(msg)),
+        &[
+            (
+                0,
+                "7346bc14f036e87ae03d0997913088f5f68411434b3cf8b54fa796a80d251f91",
+            ),
+            (
+                1,
+                "8dd446ada58a7740ecf56eb638ef775f7d5c0fd5f0c2bbbdfdec29609d3c43a2",
+            ),
+            (
+                7,
+                "dd409ccc0c60cd7f474c0beed1e1cd48140ad45d5136dc5fda5ebe283df8d3f6",
+            ),
+            (
+                8,
+                "f4c6a44b29915d3d57cf928a18ec6226bb8dd6c1136acd24965f7e7780cd69cf",
+            ),
+            (
+                9,
+                "1e1e710d08a78263773331782621088ca9fe2ee4f596f06c8f7884ca564acec1",
+            ),
+            (
+                1024,
+                "2eb89744de7f9a6f47d53db756bb2f67b127da96762a1c47a5d7bfc1f7273f5c",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_ascon_hasha() {
+    run_test_legacy_hash(
+        |msg, out| out.copy_from_slice(&AsconHasha::hash(msg)),
+        &[
+            (
+                0,
+                "aecd027026d0675f9de7a8ad8ccf512db64b1edcf0b20c388a0c7cc617aaa2c4",
+            ),
+            (
+                1,
+                "5a55f0367763d334a3174f9c17fa476eb9196a22f10daf29505633572e7756e4",
+            ),
+            (
+                7,
+                "6b6ad8a90eab00dccc182df1cec764e706461e76d303863728b8590b772e9082",
+            ),
+            (
+                8,
+                "be9332e10ad16137322968bbec1776ba3f4ecdc1183db7dbe1ac98bd66fce7b6",
+            ),
+            (
+                9,
+                "7d3e9e36b5865a874dbc7f9373fb184fa722a94dd3ee04612b5363c949b5089b",
+            ),
+            (
+                1024,
+                "14f6a0c1e5751733955b820ca67bc89bb7eb7014c88caeb5f380d75eed484fe9",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn test_ascon_xof() {
+    run_test_legacy_hash(
+        AsconXof::xof,
+        &[
+            (
+                0,
+                "5d4cbde6350ea4c174bd65b5b332f8408f99740b81aa02735eaefbcf0ba0339e",
+            ),
+            (
+                1,
+                "b2edbb27ac8397a55bc83d137c151de9ede048338fe907f0d3629e717846fedc",
+            ),
+            (
+                7,
+                "1db7476cd72064c68e736d821ea6f0c93610fe22326754f5366836871a6f5a10",
+            ),
+            (
+                8,
+                "18427d2d29df1e0202649f032f2080363fec5de72ecae11b4f98ccc75843e7cc",
+            ),
+            (
+                9,
+                "ce606e3ffcee53b113aa5a5ca3a16376a3de364352875d3360e131666a567248",
+            ),
+            (
+                1024,
+                "675b6da0d02ddd65042b7487bdefce06a4be090662ed39a703ad802c977a4b3b",
+            ),
+        ],
+    );
+
+    let mut out = [0; 45];
+    AsconXof::xof(b"abc", &mut out);
+    assert_eq!(
+        out.to_vec(),
+        from_hex(
+            "c90213a9e93b192c1d47f8aa20545f6f86686527896cb8d6530bbae9554e6dc5\
+             9b037c848e1cb3aa369cf29746"
+        )
+    );
+}
+
+#[test]
+fn test_ascon_xofa() {
+    run_test_legacy_hash(
+        AsconXofa::xof,
+        &[
+            (
+                0,
+                "7c10dffd6bb03be262d72fbe1b0f530013c6c4eadaabde278d6f29d579e3908d",
+            ),
+            (
+                1,
+                "965445c46c8e9b948edfef7b5879e06ab5f023770ea892fa4b54525008467ea3",
+            ),
+            (
+                7,
+                "00755b9d72b2632d88cb6945d536382c1e0b4957b4a44bb51c14886a6fb31a45",
+            ),
+            (
+                8,
+                "91c72f6273b6ed444bf560f2fac99e8fedddf30162688b86553eb57f1c98c20e",
+            ),
+            (
+                9,
+                "7e79768f37d213b11b4193e1d62d339954a3b9e16ccef05fd574e1330668b628",
+            ),
+            (
+                1024,
+                "8096e9bb573ea6b2c1d7acac7fb9d9f8f6c89e52a63b1b129037fd4fcc913ffb",
+            ),
+        ],
+    );
+
+    let mut out = [0; 45];
+    AsconXofa::xof(b"abc", &mut out);
+    assert_eq!(
+        out.to_vec(),
+        from_hex(
+            "71820bddaf77da5f608f10b193ed55184680fec2bcb564fc19958f9bef195016\
+             97f64d4a0a3be29659c5ccd3c4"
+        )
+    );
+}
+
+#[test]
+fn test_aead_truncated_tag() {
+    let key = Key::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let nonce = Nonce::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let (expected_cipher, expected_tag) = AEAD128::encrypt(&key, &nonce, b"ad", b"plaintext");
+    let expected_tag = *expected_tag.as_bytes();
+
+    let (cipher, tag) = AEAD128::encrypt_truncated::<8>(&key, &nonce, b"ad", b"plaintext");
+    assert_eq!(cipher, expected_cipher);
+    assert_eq!(tag.as_bytes(), &expected_tag[..8]);
+    assert_eq!(
+        AEAD128::decrypt_truncated(&key, &nonce, b"ad", &cipher, &tag),
+        Ok(b"plaintext".to_vec())
+    );
+
+    let (_, tag) = AEAD128::encrypt_truncated::<12>(&key, &nonce, b"ad", b"plaintext");
+    assert_eq!(tag.as_bytes(), &expected_tag[..12]);
+
+    // Every byte of the truncated tag is checked.
+    for i in 0..12 {
+        let mut tag = *tag.as_bytes();
+        tag[i] ^= 1;
+        assert!(AEAD128::decrypt_truncated(&key, &nonce, b"ad", &cipher, &Tag::from(tag)).is_err());
+    }
+
+    // The full length tag works too.
+    let expected_tag = Tag::from(expected_tag);
+    assert!(AEAD128::decrypt_truncated(&key, &nonce, b"ad", &cipher, &expected_tag).is_ok());
+}
+
+#[test]
+fn test_zeroize() {
+    let mut buf = *b"secret";
+    buf.zeroize();
+    assert_eq!(buf, [0; 6]);
+
+    let mut words = vec![u64::MAX; 5];
+    words.zeroize();
+    assert_eq!(words, [0; 5]);
+}
+
+#[test]
+fn test_debug_redacted() {
+    let key = Key::from([0xaa; 16]);
+    let mut encryptor = Aead128Encryptor::new(&key, &Nonce::from([0x42; 16]));
+    encryptor.update(b"plaintext");
+    let debug = format!("{:?}", encryptor);
+
+    assert!(debug.contains("nonce"));
+    assert!(!debug.contains("170"), "{debug}");
+    assert!(!debug.contains("state"), "{debug}");
+    assert!(!debug.contains("key"), "{debug}");
+}
+
+#[test]
+fn test_types_hex() {
+    let hex = "000102030405060708090a0b0c0d0e0f";
+    let nonce: Nonce = hex.parse().unwrap();
+    assert_eq!(nonce, Nonce::from(core::array::from_fn(|i| i as u8)));
+    assert_eq!(nonce.to_string(), hex);
+
+    // Uppercase is accepted too, but always displayed as lowercase.
+    let tag: Tag = "4427D64B8E1E1451FC445960F0839BB0".parse().unwrap();
+    assert_eq!(tag.to_string(), "4427d64b8e1e1451fc445960f0839bb0");
+
+    let key: Key = hex.parse().unwrap();
+    assert_eq!(key.as_bytes(), nonce.as_bytes());
+    assert_eq!(format!("{:?}", key), "Key(<redacted>)");
+
+    assert_eq!("0".parse::<Key>().unwrap_err(), Error::InvalidHex);
+    assert_eq!(
+        "zz".repeat(16).parse::<Key>().unwrap_err(),
+        Error::InvalidHex
+    );
+    // Multibyte characters must not make the parser panic.
+    assert_eq!(
+        format!("é{}", &hex[2..]).parse::<Nonce>().unwrap_err(),
+        Error::InvalidHex
+    );
+    assert_eq!(
+        "0011".parse::<Tag>().unwrap_err(),
+        Error::InvalidLength {
+            expected: 16,
+            actual: 2
+        }
+    );
+}
+
+#[test]
+fn test_types_try_from_slice() {
+    let bytes: Vec<u8> = (0..17).collect();
+
+    let nonce = Nonce::try_from(&bytes[..16]).unwrap();
+    assert_eq!(nonce.as_ref(), &bytes[..16]);
+    assert_eq!(
+        Nonce::try_from(&bytes[..]).unwrap_err(),
+        Error::InvalidLength {
+            expected: 16,
+            actual: 17
+        }
+    );
+}
+
+#[test]
+fn test_interleave() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..1000 {
+        let x: u64 = rng.gen();
+        let encoded = interleaved::encode(x);
+        for i in 0..32 {
+            // The even bits end up in the low half, the odd bits in the high half.
+            assert_eq!((encoded >> i) & 1, (x >> (2 * i)) & 1);
+            assert_eq!((encoded >> (32 + i)) & 1, (x >> (2 * i + 1)) & 1);
+        }
+        assert_eq!(interleaved::decode(encoded), x);
+    }
+}
+
+#[test]
+fn test_interleaved_rounds() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+
+    for n in 1..=12 {
+        for _ in 0..100 {
+            let state: [u64; 5] = rng.gen();
+
+            let mut expected = state;
+            round::do_n_rounds_64(&mut expected, n);
+            let mut actual = state;
+            interleaved::do_n_rounds(&mut actual, n);
+
+            assert_eq!(actual, expected, "{} rounds on {:x?}", n, state);
+        }
+    }
+}
+
+/// Checks a multi-lane permutation against `expected_rounds`, on random states with a random mix of
+/// 8 and 12 rounds.
+fn run_test_lanes(
+    permute: fn(&mut [[u64; 5]; 4], [usize; 4]),
+    expected_rounds: fn(&mut [u64; 5], usize),
+) {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..1000 {
+        let states: [[u64; 5]; 4] = rng.gen();
+        let rounds = [0; 4].map(|_| if rng.gen() { 12 } else { 8 });
+
+        let mut expected = states;
+        for (state, &n) in expected.iter_mut().zip(&rounds) {
+            expected_rounds(state, n);
+        }
+        let mut actual = states;
+        permute(&mut actual, rounds);
+
+        assert_eq!(actual, expected, "{:?} rounds on {:x?}", rounds, states);
+    }
+}
+
+#[test]
+fn test_lanes() {
+    // The lanes hold the states of the modes.
+    run_test_lanes(lanes::permute, round::do_n_rounds_encoded);
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        // Safety: SSE2 is part of x86_64.
+        run_test_lanes(
+            |states, rounds| unsafe { lanes::sse2::permute(states, rounds) },
+            round::do_n_rounds_64,
+        );
+
+        #[cfg(feature = "std")]
+        if std::is_x86_feature_detected!("avx2") {
+            // Safety: AVX2 was just detected.
+            run_test_lanes(
+                |states, rounds| unsafe { lanes::avx2::permute(states, rounds) },
+                round::do_n_rounds_64,
+            );
+        }
+    }
+}
+
+#[test]
+fn test_hash_many() {
+    let data: Vec<u8> = (0..100).map(|x| x as u8).collect();
+    // Lengths are mixed so that lanes are refilled at different times.
+    let messages: Vec<&[u8]> = (0..data.len())
+        .map(|i| &data[..(i * 37) % data.len()])
+        .collect();
+
+    let digests = hash256::hash_many(&messages);
+    assert_eq!(digests.len(), messages.len());
+    for (digest, msg) in digests.iter().zip(&messages) {
+        assert_eq!(digest, &Hash256::hash(msg));
+    }
+
+    assert!(hash256::hash_many(&[]).is_empty());
+    assert_eq!(hash256::hash_many(&[b"abc"]), [Hash256::hash(b"abc")]);
+}
+
+#[test]
+fn test_aead_encrypt_many() {
+    let key = Key::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15]);
+    let data: Vec<u8> = (0..70).map(|x| x as u8).collect();
+    let nonces: Vec<Nonce> = (0..data.len())
+        .map(|i| Nonce::from([i as u8; 16]))
+        .collect();
+    let messages: Vec<(&Nonce, &[u8], &[u8])> = nonces
+        .iter()
+        .enumerate()
+        .map(|(i, nonce)| {
+            (
+                nonce,
+                &data[..(i * 13) % 35],
+                &data[..(i * 29) % data.len()],
+            )
+        })
+        .collect();
+
+    let encrypted = AEAD128::encrypt_many(&key, &messages);
+    assert_eq!(encrypted.len(), messages.len());
+    for (encrypted, &(nonce, ad, plain)) in encrypted.iter().zip(&messages) {
+        assert_eq!(encrypted, &AEAD128::encrypt(&key, nonce, ad, plain));
+    }
+}
+
+#[test]
+fn test_permute() {
+    use rand::Rng;
+
+    fn check<const N: usize>(state: [u64; 5]) {
+        let mut expected = state;
+        round::do_n_rounds(&mut expected, N);
+        let mut actual = state;
+        round::permute::<N>(&mut actual);
+
+        assert_eq!(actual, expected, "{} rounds on {:x?}", N, state);
+    }
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..100 {
+        let state = rng.gen();
+        check::<1>(state);
+        check::<6>(state);
+        check::<8>(state);
+        check::<12>(state);
+        check::<16>(state);
+    }
+}
+
+#[test]
+fn test_permutation() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..100 {
+        let state = State::new(rng.gen());
+        assert_eq!(State::from_bytes(&state.to_bytes()), state);
+
+        // The modes use the last `n` rounds.
+        for n in 1..=12 {
+            let mut p = Permutation::new(state);
+            p.apply_rounds(12 - n, n).unwrap();
+
+            let mut expected = *state.words();
+            round::do_n_rounds(&mut expected, n);
+            assert_eq!(p.state().words(), &expected, "{} rounds", n);
+        }
+
+        // Rounds 3 to 7, split in two ranges then with explicit constants.
+        let mut p = Permutation::new(state);
+        p.apply_rounds(3, 5).unwrap();
+
+        let mut q = Permutation::new(state);
+        q.apply_rounds(3, 2).unwrap();
+        q.apply_rounds(5, 3).unwrap();
+        assert_eq!(q.state(), p.state());
+
+        let mut q = Permutation::new(state);
+        for c in [0xc3, 0xb4, 0xa5, 0x96, 0x87] {
+            q.round_with_constant(c);
+        }
+        assert_eq!(q.state(), p.state());
+
+        // Nothing at all.
+        let mut p = Permutation::new(state);
+        p.apply_rounds(12, 0).unwrap();
+        assert_eq!(p.into_state(), state);
+    }
+
+    let state = State::from_bytes(&core::array::from_fn(|i| i as u8));
+    assert_eq!(state.words()[0], 0x0706050403020100);
+    assert_eq!(
+        state.to_string(),
+        "x0=0x706050403020100 x1=0xf0e0d0c0b0a0908 x2=0x1716151413121110 \
+         x3=0x1f1e1d1c1b1a1918 x4=0x2726252423222120"
+    );
+}
+
+#[test]
+fn test_permutation_invalid_rounds() {
+    let state = State::new([1, 2, 3, 4, 5]);
+    let mut p = Permutation::new(state);
+    for (start, count) in [(8, 5), (13, 0), (0, 13), (1, usize::MAX)] {
+        let expected = Err(Error::InvalidRounds { start, count });
+        assert_eq!(p.apply_rounds(start, count), expected);
+        assert_eq!(p.inverse_rounds(start, count), expected);
+
+        let mut words = *state.words();
+        assert_eq!(round::inverse_rounds(&mut words, start, count), expected);
+        assert_eq!(&words, state.words());
+    }
+    assert_eq!(p.into_state(), state);
+}
+
+#[test]
+fn test_inverse_rounds() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..20 {
+        let state: [u64; 5] = rng.gen();
+
+        let constant = rng.gen();
+        let mut s = state;
+        round::round(&mut s, constant);
+        assert_ne!(s, state);
+        round::inverse_round(&mut s, constant);
+        assert_eq!(s, state, "constant 0x{:x}", constant);
+
+        for n in 1..=12 {
+            let mut s = state;
+            round::do_n_rounds(&mut s, n);
+            round::inverse_rounds(&mut s, 12 - n, n).unwrap();
+            assert_eq!(s, state, "{} rounds", n);
+
+            for start in 0..=12 - n {
+                let mut p = Permutation::new(State::new(state));
+                p.apply_rounds(start, n).unwrap();
+                p.inverse_rounds(start, n).unwrap();
+                assert_eq!(p.state().words(), &state, "{} rounds from {}", n, start);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_reference_aead() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..2000 {
+        let key: [u8; 16] = rng.gen();
+        let nonce: [u8; 16] = rng.gen();
+        let ad: Vec<u8> = (0..rng.gen_range(0..80)).map(|_| rng.gen()).collect();
+        let plain: Vec<u8> = (0..rng.gen_range(0..200)).map(|_| rng.gen()).collect();
+
+        let (cipher, tag) = AEAD128::encrypt(&Key::from(key), &Nonce::from(nonce), &ad, &plain);
+        let (expected_cipher, expected_tag) = reference::aead128_encrypt(&key, &nonce, &ad, &plain);
+
+        let lens = (ad.len(), plain.len());
+        assert_eq!(
+            cipher, expected_cipher,
+            "ad and plaintext of {:?} bytes",
+            lens
+        );
+        assert_eq!(
+            tag.as_bytes(),
+            &expected_tag,
+            "ad and plaintext of {:?} bytes",
+            lens
+        );
+
+        // Decryption, both ways.
+        let (key, nonce) = (Key::from(key), Nonce::from(nonce));
+        assert_eq!(
+            AEAD128::decrypt(
+                &key,
+                &nonce,
+                &ad,
+                &expected_cipher,
+                &Tag::from(expected_tag)
+            ),
+            Ok(plain.clone()),
+            "ad and plaintext of {:?} bytes",
+            lens
+        );
+        let mut buffer = expected_cipher.clone();
+        assert_eq!(
+            AEAD128::decrypt_in_place_detached(
+                &key,
+                &nonce,
+                &ad,
+                &mut buffer,
+                &Tag::from(expected_tag)
+            ),
+            Ok(()),
+            "ad and plaintext of {:?} bytes",
+            lens
+        );
+        assert_eq!(buffer, plain, "ad and plaintext of {:?} bytes", lens);
+        assert_eq!(
+            reference::aead128_decrypt(
+                key.as_bytes(),
+                nonce.as_bytes(),
+                &ad,
+                &cipher,
+                tag.as_bytes()
+            ),
+            Some(plain.clone()),
+            "ad and plaintext of {:?} bytes",
+            lens
+        );
+
+        let mut wrong_tag = expected_tag;
+        wrong_tag[rng.gen_range(0..16)] ^= 1 << rng.gen_range(0..8);
+        assert_eq!(
+            reference::aead128_decrypt(key.as_bytes(), nonce.as_bytes(), &ad, &cipher, &wrong_tag),
+            None,
+            "ad and plaintext of {:?} bytes",
+            lens
+        );
+    }
+}
+
+#[test]
+fn test_reference_hash() {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    for _ in 0..2000 {
+        let message: Vec<u8> = (0..rng.gen_range(0..200)).map(|_| rng.gen()).collect();
+
+        assert_eq!(
+            Hash256::hash(&message),
+            reference::hash256(&message),
+            "message of {} bytes",
+            message.len()
+        );
+    }
+}
+
+#[test]
+fn test_kat_aead128() {
+    let kats = kat::parse_aead(include_str!("../kat/LWC_AEAD_KAT_128_128.txt")).unwrap();
+    assert_eq!(kats.len(), 33 * 33);
+
+    for kat in kats {
+        let (cipher, tag) = AEAD128::encrypt(&kat.key, &kat.nonce, &kat.ad, &kat.plain);
+        let (expected_cipher, expected_tag) = kat.cipher.split_at(kat.plain.len());
+
+        assert_eq!(cipher, expected_cipher, "AEAD128 KAT Count = {}", kat.count);
+        assert_eq!(
+            tag.as_bytes(),
+            expected_tag,
+            "AEAD128 KAT Count = {}",
+            kat.count
+        );
+        assert_eq!(
+            AEAD128::decrypt(&kat.key, &kat.nonce, &kat.ad, &cipher, &tag).as_ref(),
+            Ok(&kat.plain),
+            "AEAD128 KAT Count = {}",
+            kat.count
+        );
+    }
+}
+
+#[test]
+fn test_kat_hash256() {
+    let kats = kat::parse_hash(include_str!("../kat/LWC_HASH_KAT_256.txt")).unwrap();
+    assert_eq!(kats.len(), 1025);
+
+    for kat in kats {
+        assert_eq!(
+            Hash256::hash(&kat.msg).as_slice(),
+            kat.digest,
+            "Hash256 KAT Count = {}",
+            kat.count
+        );
+    }
+}
+
+/// Reads an upstream KAT file from `kat/`, at runtime as it may not be checked in yet (see
+/// `kat/README.md`).
+fn read_kat(path: &str) -> String {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("kat")
+        .join(path);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+/// Checks an Ascon v1.2 AEAD with a 128 bits key against the KAT file at `path`.
+fn run_legacy_aead_kat(name: &str, path: &str, encrypt: EncryptFn<Key>, decrypt: DecryptFn<Key>) {
+    let kats = kat::parse_aead(&read_kat(path)).unwrap();
+    assert_eq!(kats.len(), 33 * 33);
+
+    for kat in kats {
+        let (cipher, tag) = encrypt(&kat.key, &kat.nonce, &kat.ad, &kat.plain);
+        let (expected_cipher, expected_tag) = kat.cipher.split_at(kat.plain.len());
+
+        assert_eq!(
+            cipher, expected_cipher,
+            "{} KAT Count = {}",
+            name, kat.count
+        );
+        assert_eq!(
+            tag.as_bytes(),
+            expected_tag,
+            "{} KAT Count = {}",
+            name,
+            kat.count
+        );
+        assert_eq!(
+            decrypt(&kat.key, &kat.nonce, &kat.ad, &cipher, &tag).as_ref(),
+            Ok(&kat.plain),
+            "{} KAT Count = {}",
+            name,
+            kat.count
+        );
+    }
+}
+
+#[test]
+#[ignore = "the upstream v1.2 KAT files are not imported yet, see kat/README.md"]
+fn test_kat_ascon128() {
+    run_legacy_aead_kat(
+        "Ascon-128",
+        "v1.2/ascon128v12/LWC_AEAD_KAT_128_128.txt",
+        Ascon128::encrypt,
+        Ascon128::decrypt,
+    );
+}
+
+#[test]
+#[ignore = "the upstream v1.2 KAT files are not imported yet, see kat/README.md"]
+fn test_kat_ascon128a() {
+    run_legacy_aead_kat(
+        "Ascon-128a",
+        "v1.2/ascon128av12/LWC_AEAD_KAT_128_128.txt",
+        Ascon128a::encrypt,
+        Ascon128a::decrypt,
+    );
+}
+
+/// Checks an Ascon v1.2 hash or XOF against the KAT file at `path`, `hash` filling its output
+/// buffer with the digest of the message.
+fn run_legacy_hash_kat(name: &str, path: &str, hash: fn(&[u8], &mut [u8])) {
+    let kats = kat::parse_hash(&read_kat(path)).unwrap();
+    assert_eq!(kats.len(), 1025);
+
+    for kat in kats {
+        let mut digest = vec![0; kat.digest.len()];
+        hash(&kat.msg, &mut digest);
+
+        assert_eq!(digest, kat.digest, "{} KAT Count = {}", name, kat.count);
+    }
+}
+
+#[test]
+#[ignore = "the upstream v1.2 KAT files are not imported yet, see kat/README.md"]
+fn test_kat_ascon_hash() {
+    run_legacy_hash_kat(
+        "Ascon-Hash",
+        "v1.2/asconhashv12/LWC_HASH_KAT_256.txt",
+        |msg, out| out.copy_from_slice(&AsconHash::hash(msg)),
+    );
+}
+
+#[test]
+#[ignore = "the upstream v1.2 KAT files are not imported yet, see kat/README.md"]
+fn test_kat_ascon_hasha() {
+    run_legacy_hash_kat(
+        "Ascon-Hasha",
+        "v1.2/asconhashav12/LWC_HASH_KAT_256.txt",
+        |msg, out| out.copy_from_slice(&AsconHasha::hash(msg)),
+    );
+}
+
+#[test]
+#[ignore = "the upstream v1.2 KAT files are not imported yet, see kat/README.md"]
+fn test_kat_ascon_xof() {
+    run_legacy_hash_kat(
+        "Ascon-Xof",
+        "v1.2/asconxofv12/LWC_HASH_KAT_256.txt",
+        AsconXof::xof,
+    );
+}
+
+#[test]
+#[ignore = "the upstream v1.2 KAT files are not imported yet, see kat/README.md"]
+fn test_kat_ascon_xofa() {
+    run_legacy_hash_kat(
+        "Ascon-Xofa",
+        "v1.2/asconxofav12/LWC_HASH_KAT_256.txt",
+        AsconXofa::xof,
+    );
+}
+
+#[test]
+fn test_kat_write() {
+    use std::fmt::Write;
+
+    let text = include_str!("../kat/LWC_AEAD_KAT_128_128.txt");
+    let written = kat::parse_aead(text)
+        .unwrap()
+        .iter()
+        .fold(String::new(), |mut out, kat| {
+            write!(out, "{}", kat).unwrap();
+            out
+        });
+    assert!(written == text, "AEAD KAT file not written back as is");
+
+    let text = include_str!("../kat/LWC_HASH_KAT_256.txt");
+    let written = kat::parse_hash(text)
+        .unwrap()
+        .iter()
+        .fold(String::new(), |mut out, kat| {
+            write!(out, "{}", kat).unwrap();
+            out
+        });
+    assert!(written == text, "hash KAT file not written back as is");
+
+    let mut out = String::new();
+    kat::write_record(&mut out, 3, &[("Key", &[0xab, 0x01]), ("Msg", &[])]).unwrap();
+    assert_eq!(out, "Count = 3\nKey = AB01\nMsg = \n\n");
+}
+
+#[test]
+fn test_kat_invalid() {
+    let invalid = |line| Err(Error::InvalidKat { line });
+
+    assert_eq!(kat::parse_hash(""), Ok(vec![]));
+    assert_eq!(
+        kat::parse_hash("Count = 1\nMsg = \nMD = 00\n\n\nCount = 2\nMsg = 0\nMD = 00\n"),
+        invalid(7)
+    );
+    // Missing, extra and misplaced fields.
+    assert_eq!(kat::parse_hash("Count = 1\nMsg = \n"), invalid(3));
+    assert_eq!(
+        kat::parse_hash("Count = 1\nMsg = \nMD = \nMD = \n"),
+        invalid(4)
+    );
+    assert_eq!(kat::parse_hash("Count = 1\nMD = \nMsg = \n"), invalid(2));
+    assert_eq!(kat::parse_hash("\nCount = x\nMsg = \nMD = \n"), invalid(2));
+    assert_eq!(kat::parse_hash("Count = 1\nMsg\nMD = \n"), invalid(2));
+    assert!(matches!(
+        kat::parse_aead("Count = 1\nKey = 00\nNonce = \nPT = \nAD = \nCT = \n"),
+        Err(Error::InvalidKat { line: 2 })
+    ));
+}
@@ -0,0 +1,188 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
+
+use crate::{
+    utils::{ct_eq, Zeroize},
+    Error,
+};
+
+/// Decodes a single hex character.
+fn nibble(c: u8) -> Result<u8, Error> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(Error::InvalidHex),
+    }
+}
+
+/// Decodes the hex string `s` (of any case) into exactly `N` bytes.
+///
+/// This works on the raw bytes of `s`, so any non-ASCII character is simply invalid.
+fn decode_hex<const N: usize>(s: &str) -> Result<[u8; N], Error> {
+    let s = s.as_bytes();
+    if !s.len().is_multiple_of(2) {
+        return Err(Error::InvalidHex);
+    }
+
+    if s.len() != 2 * N {
+        // Report a bad character before a bad length.
+        for &c in s {
+            nibble(c)?;
+        }
+
+        return Err(Error::InvalidLength {
+            expected: N,
+            actual: s.len() / 2,
+        });
+    }
+
+    let mut out = [0; N];
+    for (o, c) in out.iter_mut().zip(s.chunks_exact(2)) {
+        *o = nibble(c[0])? << 4 | nibble(c[1])?;
+    }
+
+    Ok(out)
+}
+
+/// Decodes the hex string `s` (of any case) into as many bytes as it holds.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_hex_vec(s: &str) -> Result<Vec<u8>, Error> {
+    let s = s.as_bytes();
+    if !s.len().is_multiple_of(2) {
+        return Err(Error::InvalidHex);
+    }
+
+    s.chunks_exact(2)
+        .map(|c| Ok(nibble(c[0])? << 4 | nibble(c[1])?))
+        .collect()
+}
+
+/// Writes `bytes` as lowercase hex.
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    for b in bytes {
+        write!(f, "{:02x}", b)?;
+    }
+
+    Ok(())
+}
+
+/// Implements the conversions shared by the byte array newtypes, `$len` bytes long.
+///
+/// Generic newtypes give their generic parameters first, e.g. `[const N: usize] Tag<N>, N`.
+macro_rules! impl_bytes_newtype {
+    ($name:ident, $len:expr) => {
+        impl_bytes_newtype!([] $name, $len);
+    };
+    ([$($generics:tt)*] $name:ty, $len:expr) => {
+        impl<$($generics)*> $name {
+            /// Length in bytes.
+            pub const LEN: usize = $len;
+
+            pub fn as_bytes(&self) -> &[u8; $len] {
+                &self.0
+            }
+        }
+
+        impl<$($generics)*> From<[u8; $len]> for $name {
+            fn from(bytes: [u8; $len]) -> Self {
+                Self(bytes)
+            }
+        }
+
+        impl<$($generics)*> TryFrom<&[u8]> for $name {
+            type Error = Error;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+                let bytes: [u8; $len] = bytes.try_into().map_err(|_| Error::InvalidLength {
+                    expected: Self::LEN,
+                    actual: bytes.len(),
+                })?;
+
+                Ok(Self(bytes))
+            }
+        }
+
+        impl<$($generics)*> FromStr for $name {
+            type Err = Error;
+
+            /// Parses a hex string of `2 * LEN` characters.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                decode_hex(s).map(Self)
+            }
+        }
+
+        impl<$($generics)*> fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write_hex(f, &self.0)
+            }
+        }
+
+        impl<$($generics)*> AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+    };
+}
+
+/// 128 bits secret key.
+///
+/// It is wiped when dropped, and never shown by `Debug`.
+#[derive(Clone)]
+pub struct Key([u8; 16]);
+
+impl_bytes_newtype!(Key, 16);
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key(<redacted>)")
+    }
+}
+
+impl Drop for Key {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// 160 bits secret key, only used by `Ascon-80pq`.
+///
+/// Like [`Key`], it is wiped when dropped, and never shown by `Debug`.
+#[derive(Clone)]
+pub struct Key160([u8; 20]);
+
+impl_bytes_newtype!(Key160, 20);
+
+impl fmt::Debug for Key160 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key160(<redacted>)")
+    }
+}
+
+impl Drop for Key160 {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// 128 bits nonce, which must never be used twice with the same [`Key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nonce([u8; 16]);
+
+impl_bytes_newtype!(Nonce, 16);
+
+/// Authentication tag: 128 bits, or its first `N` bytes once truncated.
+///
+/// Comparing two tags with `==` runs in constant time.
+#[derive(Debug, Clone, Copy, Eq)]
+pub struct Tag<const N: usize = 16>([u8; N]);
+
+impl_bytes_newtype!([const N: usize] Tag<N>, N);
+
+impl<const N: usize> PartialEq for Tag<N> {
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq(&self.0, &other.0)
+    }
+}
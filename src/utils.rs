@@ -1,3 +1,57 @@
 pub fn pad_u64(value: u64, size: usize) -> u64 {
     value ^ (1 << (8 * size))
 }
+
+/// Forwards to `log::debug!` when the `log` feature is enabled, and expands to nothing otherwise.
+///
+/// Only public data may be traced: lengths and steps, never a key, a state, a tag or a plaintext.
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)*)
+    };
+}
+pub(crate) use debug;
+
+/// Compares two byte slices in constant time.
+///
+/// Only the lengths (which are public) can make this return early: the content is always
+/// entirely read.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    diff(a.iter().zip(b)) == 0
+}
+
+/// ORs together the XOR of every pair, which is 0 only if all pairs are equal.
+///
+/// Every pair is read whatever the values: there is no branch on them, and `black_box` keeps the
+/// compiler from turning the accumulation into an early exit.
+pub(crate) fn diff<'a>(pairs: impl Iterator<Item = (&'a u8, &'a u8)>) -> u8 {
+    pairs.fold(0, |acc, (x, y)| acc | core::hint::black_box(x ^ y))
+}
+
+/// Secret values, which can be wiped in a way the compiler can not optimize out even if they are
+/// never read again.
+pub trait Zeroize {
+    /// Overwrites `self` with zeros.
+    fn zeroize(&mut self);
+}
+
+impl<T: Copy + Default> Zeroize for [T] {
+    fn zeroize(&mut self) {
+        for x in self.iter_mut() {
+            // Safety: `x` is a valid and aligned `&mut T`.
+            unsafe { core::ptr::write_volatile(x, T::default()) };
+        }
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Zeroize for [T; N] {
+    fn zeroize(&mut self) {
+        self.as_mut_slice().zeroize();
+    }
+}
@@ -0,0 +1,196 @@
+//! Tests of the `ascon hash` subcommand, run on files in a temporary directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+use ascon::hash256::Hash256;
+
+/// Creates an empty directory for the test `name`.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+/// Runs `ascon hash` with `args` in `dir`.
+fn hash(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ascon"))
+        .current_dir(dir)
+        .arg("hash")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn digest(content: &[u8]) -> String {
+    Hash256::hash(content)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn test_hash() {
+    let dir = temp_dir("hash");
+    fs::write(dir.join("a"), b"abc").unwrap();
+    fs::write(dir.join("b"), b"").unwrap();
+
+    let output = hash(&dir, &["a", "b"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout(&output),
+        format!("{}  a\n{}  b\n", digest(b"abc"), digest(b""))
+    );
+
+    let output = hash(&dir, &["a", "missing"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), format!("{}  a\n", digest(b"abc")));
+    assert!(stderr(&output).contains("missing"));
+}
+
+#[test]
+fn test_check_ok() {
+    let dir = temp_dir("check_ok");
+    fs::write(dir.join("a"), b"abc").unwrap();
+    fs::write(dir.join("b"), b"").unwrap();
+
+    // Both separators, upper case hex, and a blank line.
+    let manifest = format!(
+        "{}  a\n\n{} *b\n",
+        digest(b"abc").to_uppercase(),
+        digest(b"")
+    );
+    fs::write(dir.join("SUMS"), manifest).unwrap();
+
+    let output = hash(&dir, &["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a: OK\nb: OK\n");
+    assert_eq!(stderr(&output), "");
+
+    // The output of `ascon hash` checks fine.
+    let sums = hash(&dir, &["a", "b"]).stdout;
+    fs::write(dir.join("SUMS"), sums).unwrap();
+    assert_eq!(hash(&dir, &["-c", "SUMS"]).status.code(), Some(0));
+}
+
+#[test]
+fn test_check_crlf() {
+    let dir = temp_dir("check_crlf");
+    fs::write(dir.join("a"), b"abc").unwrap();
+    fs::write(dir.join("b"), b"").unwrap();
+
+    let manifest = format!("{}  a\r\n{}  b\r\n", digest(b"abc"), digest(b""));
+    fs::write(dir.join("SUMS"), manifest).unwrap();
+
+    let output = hash(&dir, &["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "a: OK\nb: OK\n");
+}
+
+#[test]
+fn test_check_mismatch() {
+    let dir = temp_dir("check_mismatch");
+    fs::write(dir.join("a"), b"abc").unwrap();
+    fs::write(dir.join("b"), b"abd").unwrap();
+
+    let manifest = format!("{}  a\n{}  b\n", digest(b"abc"), digest(b"abc"));
+    fs::write(dir.join("SUMS"), manifest).unwrap();
+
+    let output = hash(&dir, &["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "a: OK\nb: FAILED\n");
+}
+
+#[test]
+fn test_check_missing_file() {
+    let dir = temp_dir("check_missing_file");
+    fs::write(dir.join("a"), b"abc").unwrap();
+
+    // A missing file wins over a mismatch.
+    let manifest = format!("{}  missing\n{}  a\n", digest(b"abc"), digest(b"abd"));
+    fs::write(dir.join("SUMS"), manifest).unwrap();
+
+    let output = hash(&dir, &["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "missing: FAILED open or read\na: FAILED\n");
+
+    // So does a missing manifest.
+    let output = hash(&dir, &["--check", "MISSING"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("MISSING"));
+}
+
+#[test]
+fn test_check_malformed() {
+    let dir = temp_dir("check_malformed");
+    fs::write(dir.join("a"), b"abc").unwrap();
+
+    let valid = format!("{}  a", digest(b"abc"));
+    let malformed = [
+        // Single space.
+        format!("{} a", digest(b"abc")),
+        // Digest too short, too long, or not hex.
+        format!("{}  a", &digest(b"abc")[1..]),
+        format!("{}0  a", digest(b"abc")),
+        format!("g{}  a", &digest(b"abc")[1..]),
+        // No path.
+        format!("{}  ", digest(b"abc")),
+        // Unknown escape sequence.
+        format!("\\{}  \\t", digest(b"abc")),
+        "not a manifest line".to_owned(),
+    ];
+
+    for line in &malformed {
+        // The other lines are still checked.
+        fs::write(dir.join("SUMS"), format!("{}\n{}\n", line, valid)).unwrap();
+
+        let output = hash(&dir, &["--check", "SUMS"]);
+        assert_eq!(output.status.code(), Some(2), "{:?}", line);
+        assert_eq!(stdout(&output), "a: OK\n", "{:?}", line);
+        assert!(
+            stderr(&output).contains("SUMS:1: improperly formatted line"),
+            "{:?}",
+            line
+        );
+    }
+
+    fs::write(dir.join("SUMS"), "not a manifest line\n").unwrap();
+    let output = hash(&dir, &["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("no properly formatted lines"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_escaped_names() {
+    let dir = temp_dir("escaped_names");
+    fs::write(dir.join("a\\b"), b"abc").unwrap();
+    fs::write(dir.join("c\nd"), b"").unwrap();
+    fs::write(dir.join("e"), b"abd").unwrap();
+
+    let output = hash(&dir, &["a\\b", "c\nd", "e"]);
+    assert_eq!(output.status.code(), Some(0));
+    let sums = format!(
+        "\\{}  a\\\\b\n\\{}  c\\nd\n{}  e\n",
+        digest(b"abc"),
+        digest(b""),
+        digest(b"abd")
+    );
+    assert_eq!(stdout(&output), sums);
+
+    fs::write(dir.join("SUMS"), sums).unwrap();
+    let output = hash(&dir, &["--check", "SUMS"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "\\a\\\\b: OK\n\\c\\nd: OK\ne: OK\n");
+}